@@ -6,7 +6,7 @@ use std::fs::File;
 use std::io::prelude::*;
 
 use test::Bencher;
-use unidiff::PatchSet;
+use unidiff::{LineRef, PatchRef, PatchSet, Visitor};
 
 #[bench]
 fn bench_parse_diff_simple(b: &mut Bencher) {
@@ -20,3 +20,40 @@ fn bench_parse_diff_simple(b: &mut Bencher) {
         patch.parse(&buf).unwrap();
     });
 }
+
+#[bench]
+fn bench_parse_diff_borrowed(b: &mut Bencher) {
+    let mut buf = String::new();
+    File::open("tests/fixtures/sample0.diff")
+        .and_then(|mut r| r.read_to_string(&mut buf))
+        .unwrap();
+
+    b.iter(|| {
+        PatchRef::parse(&buf).unwrap();
+    });
+}
+
+#[bench]
+fn bench_visit_diff_counts(b: &mut Bencher) {
+    struct Counts(usize, usize);
+
+    impl<'a> Visitor<'a> for Counts {
+        fn on_line(&mut self, line: LineRef<'a>) {
+            if line.is_added() {
+                self.0 += 1;
+            } else if line.is_removed() {
+                self.1 += 1;
+            }
+        }
+    }
+
+    let mut buf = String::new();
+    File::open("tests/fixtures/sample0.diff")
+        .and_then(|mut r| r.read_to_string(&mut buf))
+        .unwrap();
+
+    b.iter(|| {
+        let mut counts = Counts(0, 0);
+        unidiff::visit(&buf, &mut counts).unwrap();
+    });
+}
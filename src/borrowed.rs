@@ -0,0 +1,455 @@
+//! Diffs parsed without copying, borrowing their text from the parsed input
+use std::fmt;
+
+use crate::lossless::{Header, Raw};
+use crate::visit::{visit_with, FileHeader, HunkHeader, Visitor};
+use crate::{ExtendedHeader, Hunk, Line, LineType, PatchSet, PatchedFile, Result};
+
+/// A diff line borrowing its value from the parsed input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineRef<'a> {
+    /// Source file line number
+    pub source_line_no: Option<usize>,
+    /// Target file line number
+    pub target_line_no: Option<usize>,
+    /// Diff file line number
+    pub diff_line_no: usize,
+    /// Diff line type
+    pub line_type: LineType,
+    /// Diff line content value
+    pub value: &'a str,
+}
+
+impl<'a> LineRef<'a> {
+    /// Diff line type is added
+    pub fn is_added(&self) -> bool {
+        self.line_type == LineType::Added
+    }
+
+    /// Diff line type is removed
+    pub fn is_removed(&self) -> bool {
+        self.line_type == LineType::Removed
+    }
+
+    /// Diff line type is context
+    pub fn is_context(&self) -> bool {
+        self.line_type == LineType::Context
+    }
+
+    /// Owned line with a copy of the value
+    pub fn into_owned(self) -> Line {
+        Line {
+            source_line_no: self.source_line_no,
+            target_line_no: self.target_line_no,
+            diff_line_no: self.diff_line_no,
+            line_type: self.line_type,
+            value: self.value.to_owned(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for LineRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.line_type, self.value)
+    }
+}
+
+/// A hunk borrowing its lines from the parsed input
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HunkRef<'a> {
+    added: usize,
+    removed: usize,
+    /// Source file starting line number
+    pub source_start: usize,
+    /// Source file changes length
+    pub source_length: usize,
+    /// Target file starting line number
+    pub target_start: usize,
+    /// Target file changes length
+    pub target_length: usize,
+    /// Section header
+    pub section_header: &'a str,
+    lines: Vec<LineRef<'a>>,
+    raw: Option<&'a str>,
+}
+
+impl<'a> HunkRef<'a> {
+    /// Count of lines added
+    pub fn added(&self) -> usize {
+        self.added
+    }
+
+    /// Count of lines removed
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
+    /// Lines in this hunk
+    pub fn lines(&self) -> &[LineRef<'a>] {
+        &self.lines
+    }
+
+    /// Owned hunk with copies of the lines
+    pub fn into_owned(self) -> Hunk {
+        self.into_owned_with(false)
+    }
+
+    /// Owned hunk, keeping a copy of its text when `lossless`
+    fn into_owned_with(self, lossless: bool) -> Hunk {
+        let mut hunk = Hunk::new(
+            self.source_start,
+            self.source_length,
+            self.target_start,
+            self.target_length,
+            self.section_header,
+        );
+        for line in self.lines {
+            hunk.append(line.into_owned());
+        }
+        if lossless {
+            let parsed = hunk.to_string();
+            hunk.raw = Raw(self.raw.map(|raw| (raw.to_owned(), parsed)));
+        }
+        hunk
+    }
+
+    fn new(header: HunkHeader<'a>) -> HunkRef<'a> {
+        HunkRef {
+            added: 0,
+            removed: 0,
+            source_start: header.source_start,
+            source_length: header.source_length,
+            target_start: header.target_start,
+            target_length: header.target_length,
+            section_header: header.section_header,
+            lines: vec![],
+            raw: None,
+        }
+    }
+
+    fn push(&mut self, line: LineRef<'a>) {
+        if line.is_added() {
+            self.added += 1;
+        } else if line.is_removed() {
+            self.removed += 1;
+        }
+        self.lines.push(line);
+    }
+}
+
+/// A patched file borrowing its names and lines from the parsed input
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatchedFileRef<'a> {
+    /// Source file name
+    pub source_file: &'a str,
+    /// Source file timestamp
+    pub source_timestamp: Option<&'a str>,
+    /// Target file name
+    pub target_file: &'a str,
+    /// Target file timestamp
+    pub target_timestamp: Option<&'a str>,
+    extended_headers: Vec<ExtendedHeader>,
+    binary: bool,
+    hunks: Vec<HunkRef<'a>>,
+    raw_header: Option<&'a str>,
+}
+
+impl<'a> PatchedFileRef<'a> {
+    fn new(source_file: &'a str, target_file: &'a str) -> PatchedFileRef<'a> {
+        PatchedFileRef {
+            source_file,
+            source_timestamp: None,
+            target_file,
+            target_timestamp: None,
+            extended_headers: vec![],
+            binary: false,
+            hunks: vec![],
+            raw_header: None,
+        }
+    }
+
+    /// Count of lines added
+    pub fn added(&self) -> usize {
+        self.hunks.iter().map(|h| h.added).sum()
+    }
+
+    /// Count of lines removed
+    pub fn removed(&self) -> usize {
+        self.hunks.iter().map(|h| h.removed).sum()
+    }
+
+    /// Is this file binary, changed without hunks
+    pub fn is_binary_file(&self) -> bool {
+        self.binary
+    }
+
+    /// Git extended headers of this file
+    pub fn extended_headers(&self) -> &[ExtendedHeader] {
+        &self.extended_headers
+    }
+
+    /// Hunks in this file
+    pub fn hunks(&self) -> &[HunkRef<'a>] {
+        &self.hunks
+    }
+
+    /// Owned file with copies of the names and lines
+    pub fn into_owned(self) -> PatchedFile {
+        self.into_owned_with(false)
+    }
+
+    /// Owned file, keeping a copy of the text of its header and hunks when `lossless`
+    fn into_owned_with(self, lossless: bool) -> PatchedFile {
+        let mut file = PatchedFile::with_hunks(
+            self.source_file,
+            self.target_file,
+            self.hunks
+                .into_iter()
+                .map(|h| h.into_owned_with(lossless))
+                .collect(),
+        );
+        file.source_timestamp = self.source_timestamp.map(str::to_owned);
+        file.target_timestamp = self.target_timestamp.map(str::to_owned);
+        file.extended_headers = self.extended_headers;
+        file.binary = self.binary;
+        if lossless {
+            let parsed = Header::of(&file);
+            file.raw_header = Raw(self.raw_header.map(|raw| (raw.to_owned(), parsed)));
+        }
+        file
+    }
+}
+
+/// A patch set borrowing its text from the parsed input
+///
+/// Parsing into a `PatchRef` copies no line, which keeps the memory used by large diffs
+/// close to the size of their text.
+///
+/// ```
+/// use unidiff::PatchRef;
+///
+/// let diff = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-old\n+new\n".to_owned();
+/// let patch = PatchRef::parse(&diff).unwrap();
+/// assert_eq!("new", patch.files()[0].hunks()[0].lines()[1].value);
+/// let owned = patch.into_owned();
+/// assert_eq!("new", owned[0][0][1].value);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatchRef<'a> {
+    files: Vec<PatchedFileRef<'a>>,
+    lenient: bool,
+    trailer: &'a str,
+}
+
+impl<'a> PatchRef<'a> {
+    /// Parse diff from string
+    pub fn parse(input: &'a str) -> Result<PatchRef<'a>> {
+        PatchRef::parse_with(input, 0, false)
+    }
+
+    /// Parse diff from string, ignoring hunk header lengths like `PatchSet::set_lenient`
+    pub fn parse_lenient(input: &'a str) -> Result<PatchRef<'a>> {
+        PatchRef::parse_with(input, 0, true)
+    }
+
+    /// Count of patched files
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Files in this patch set
+    pub fn files(&self) -> &[PatchedFileRef<'a>] {
+        &self.files
+    }
+
+    /// Owned patch set with copies of the files
+    pub fn into_owned(self) -> PatchSet {
+        let mut patch = PatchSet::new();
+        patch.set_lenient(self.lenient);
+        patch.append(self);
+        patch
+    }
+
+    /// Parse diff from string, keeping the text of each file header and hunk
+    fn parse_with(input: &'a str, line_offset: usize, lenient: bool) -> Result<PatchRef<'a>> {
+        let mut builder = Builder::new(input, line_offset);
+        visit_with(input, line_offset, lenient, &mut builder)?;
+        Ok(builder.finish(lenient))
+    }
+}
+
+/// Visitor building a `PatchRef` from the events of its diff
+struct Builder<'a> {
+    input: &'a str,
+    /// byte offsets of the lines, to keep the text files and hunks are parsed from
+    offsets: Vec<usize>,
+    /// count of lines before the input, included in line numbers
+    line_offset: usize,
+    /// start of the text not yet kept with a file or hunk, as a line index
+    raw_start: usize,
+    /// start of the current hunk, as a line index
+    hunk_start: usize,
+    files: Vec<PatchedFileRef<'a>>,
+    current_file: Option<PatchedFileRef<'a>>,
+    git_header_found: bool,
+}
+
+impl<'a> Builder<'a> {
+    fn new(input: &'a str, line_offset: usize) -> Builder<'a> {
+        let mut offsets = vec![0];
+        offsets.extend(input.split_inclusive('\n').scan(0, |end, line| {
+            *end += line.len();
+            Some(*end)
+        }));
+        Builder {
+            input,
+            offsets,
+            line_offset,
+            raw_start: 0,
+            hunk_start: 0,
+            files: vec![],
+            current_file: None,
+            git_header_found: false,
+        }
+    }
+
+    /// Index in the input of the line at `diff_line_no`
+    fn index(&self, diff_line_no: usize) -> usize {
+        diff_line_no - self.line_offset
+    }
+
+    /// Text from the last text kept to the line at `end`, excluded
+    fn take_raw(&mut self, end: usize) -> &'a str {
+        let raw = &self.input[self.offsets[self.raw_start]..self.offsets[end]];
+        self.raw_start = end;
+        raw
+    }
+
+    fn finish(self, lenient: bool) -> PatchRef<'a> {
+        PatchRef {
+            files: self.files,
+            lenient,
+            trailer: &self.input[self.offsets[self.raw_start]..],
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Builder<'a> {
+    fn on_file_header(&mut self, header: FileHeader<'a>, _diff_line_no: usize) {
+        match header {
+            FileHeader::Git {
+                source_file,
+                target_file,
+            } => {
+                self.current_file = Some(PatchedFileRef::new(source_file, target_file));
+                self.git_header_found = true;
+            }
+            FileHeader::Binary {
+                source_file,
+                target_file,
+            } => {
+                self.current_file = Some(PatchedFileRef::new(source_file, target_file));
+            }
+            FileHeader::Unified {
+                source_file,
+                source_timestamp,
+                target_file,
+                target_timestamp,
+            } => {
+                let extended_headers = match self.current_file {
+                    Some(ref mut patched_file) if self.git_header_found => {
+                        std::mem::take(&mut patched_file.extended_headers)
+                    }
+                    _ => Vec::new(),
+                };
+                let mut patched_file = PatchedFileRef::new(source_file, target_file);
+                patched_file.source_timestamp = Some(source_timestamp.unwrap_or(""));
+                patched_file.target_timestamp = Some(target_timestamp.unwrap_or(""));
+                patched_file.extended_headers = extended_headers;
+                self.current_file = Some(patched_file);
+            }
+        }
+    }
+
+    fn on_extended_header(&mut self, header: ExtendedHeader, _diff_line_no: usize) {
+        if let Some(ref mut patched_file) = self.current_file {
+            patched_file.extended_headers.push(header);
+        }
+    }
+
+    fn on_hunk_header(&mut self, header: HunkHeader<'a>, diff_line_no: usize) {
+        if let Some(mut patched_file) = self.current_file.take() {
+            // text between hunks is kept with the next hunk
+            if patched_file.hunks.is_empty() {
+                patched_file.raw_header = Some(self.take_raw(self.index(diff_line_no) - 1));
+            }
+            self.hunk_start = self.raw_start;
+            let mut hunk = HunkRef::new(header);
+            hunk.raw = Some(self.take_raw(self.index(diff_line_no)));
+            patched_file.hunks.push(hunk);
+            self.current_file = Some(patched_file);
+        }
+    }
+
+    fn on_line(&mut self, line: LineRef<'a>) {
+        self.raw_start = self.index(line.diff_line_no);
+        let raw = &self.input[self.offsets[self.hunk_start]..self.offsets[self.raw_start]];
+        if let Some(hunk) = self
+            .current_file
+            .as_mut()
+            .and_then(|patched_file| patched_file.hunks.last_mut())
+        {
+            hunk.push(line);
+            hunk.raw = Some(raw);
+        }
+    }
+
+    fn on_binary(&mut self, _line: &'a str, _diff_line_no: usize) {
+        if let Some(ref mut patched_file) = self.current_file {
+            patched_file.binary = true;
+        }
+    }
+
+    fn on_file_end(&mut self, diff_line_no: usize) {
+        if let Some(mut patched_file) = self.current_file.take() {
+            if patched_file.hunks.is_empty() {
+                patched_file.raw_header = Some(self.take_raw(self.index(diff_line_no) - 1));
+            }
+            self.files.push(patched_file);
+            self.git_header_found = false;
+        }
+    }
+}
+
+impl PatchSet {
+    /// Add the files of a borrowed patch set, copying them
+    fn append(&mut self, patch: PatchRef) {
+        // text left over from the previous input comes before the new text
+        let mut prefix = std::mem::take(&mut self.trailer);
+        for file in patch.files {
+            let mut file = file.into_owned_with(self.lossless);
+            if !prefix.is_empty() {
+                if let Some((ref mut raw, _)) = file.raw_header.0 {
+                    raw.insert_str(0, &prefix);
+                }
+                prefix.clear();
+            }
+            self.files.push(file);
+        }
+        prefix.push_str(patch.trailer);
+        self.trailer = prefix;
+    }
+
+    /// Parse diff from string into this patch set, borrowing lines until they are copied
+    ///
+    /// Line numbers count the `line_offset` lines before the input.
+    pub(crate) fn parse_str(&mut self, input: &str, line_offset: usize) -> Result<()> {
+        let patch = PatchRef::parse_with(input, line_offset, self.lenient)?;
+        self.append(patch);
+        Ok(())
+    }
+}
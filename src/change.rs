@@ -0,0 +1,260 @@
+//! Context free changes, used to rebuild hunks after transforming a patch
+use std::collections::BTreeMap;
+
+use crate::{Hunk, Line, LineType};
+
+/// Context lines around rebuilt hunks, as git uses by default
+pub(crate) const CONTEXT: usize = 3;
+
+/// Value of the line following a last line without newline
+const NO_NEWLINE: &str = " No newline at end of file";
+
+/// A run of source lines replaced by target lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Change {
+    /// First removed source line, or the source line insertions go before
+    pub source_start: usize,
+    /// Removed source lines
+    pub removed: Vec<String>,
+    /// Added target lines
+    pub added: Vec<String>,
+    /// The last removed line is the last line of the source, without newline
+    pub removed_no_newline: bool,
+    /// The last added line is the last line of the target, without newline
+    pub added_no_newline: bool,
+}
+
+impl Change {
+    /// Change replacing the source lines from `source_start`
+    pub fn new(source_start: usize, removed: Vec<String>, added: Vec<String>) -> Change {
+        Change {
+            source_start,
+            removed,
+            added,
+            removed_no_newline: false,
+            added_no_newline: false,
+        }
+    }
+
+    /// Source line following this change
+    pub fn source_end(&self) -> usize {
+        self.source_start + self.removed.len()
+    }
+
+    /// Change of line count caused by this change
+    pub fn delta(&self) -> isize {
+        self.added.len() as isize - self.removed.len() as isize
+    }
+}
+
+/// Changes made by hunks, in source order
+pub(crate) fn changes(hunks: &[Hunk]) -> Vec<Change> {
+    let mut changes = vec![];
+    for hunk in hunks {
+        let mut source_line_no = hunk.source_first();
+        let mut current: Option<Change> = None;
+        let mut previous = LineType::Context;
+        for line in hunk.lines() {
+            if line.is_context() {
+                changes.extend(current.take());
+                source_line_no += 1;
+                previous = line.line_type;
+                continue;
+            }
+            if line.line_type == LineType::NoNewlineMarker {
+                if let Some(ref mut change) = current {
+                    match previous {
+                        LineType::Removed => change.removed_no_newline = true,
+                        LineType::Added => change.added_no_newline = true,
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+            if !line.is_added() && !line.is_removed() {
+                continue;
+            }
+            previous = line.line_type;
+            let change = current.get_or_insert_with(|| Change::new(source_line_no, vec![], vec![]));
+            if line.is_added() {
+                change.added.push(line.value.clone());
+            } else {
+                change.removed.push(line.value.clone());
+                source_line_no += 1;
+            }
+        }
+        changes.extend(current);
+    }
+    changes
+}
+
+/// Source lines known from hunks, by line number
+pub(crate) fn source_lines(hunks: &[Hunk]) -> BTreeMap<usize, String> {
+    let mut known = BTreeMap::new();
+    for hunk in hunks {
+        let mut source_line_no = hunk.source_first();
+        for line in hunk.lines() {
+            if line.is_context() || line.is_removed() {
+                known.insert(source_line_no, line.value.clone());
+                source_line_no += 1;
+            }
+        }
+    }
+    known
+}
+
+/// Line number of the last source line, when hunks show it has no newline
+pub(crate) fn source_no_newline(hunks: &[Hunk]) -> Option<usize> {
+    no_newline(hunks, Hunk::source_first, Line::is_removed)
+}
+
+/// Line number of the last target line, when hunks show it has no newline
+pub(crate) fn target_no_newline(hunks: &[Hunk]) -> Option<usize> {
+    no_newline(hunks, Hunk::target_first, Line::is_added)
+}
+
+/// Line number of a line of one side followed by a no newline marker
+fn no_newline(
+    hunks: &[Hunk],
+    first: fn(&Hunk) -> usize,
+    is_side: fn(&Line) -> bool,
+) -> Option<usize> {
+    for hunk in hunks {
+        let mut line_no = first(hunk);
+        let mut previous_on_side = false;
+        for line in hunk.lines() {
+            if line.line_type == LineType::NoNewlineMarker && previous_on_side {
+                return Some(line_no - 1);
+            }
+            previous_on_side = line.is_context() || is_side(line);
+            if previous_on_side {
+                line_no += 1;
+            }
+        }
+    }
+    None
+}
+
+/// Target lines known from hunks, by line number
+pub(crate) fn target_lines(hunks: &[Hunk]) -> BTreeMap<usize, String> {
+    let mut known = BTreeMap::new();
+    for hunk in hunks {
+        let mut target_line_no = hunk.target_first();
+        for line in hunk.lines() {
+            if line.is_context() || line.is_added() {
+                known.insert(target_line_no, line.value.clone());
+                target_line_no += 1;
+            }
+        }
+    }
+    known
+}
+
+/// Target line number of a source line, unless a change removes it
+pub(crate) fn to_target(changes: &[Change], source_line_no: usize) -> Option<usize> {
+    let mut delta = 0isize;
+    for change in changes {
+        if source_line_no < change.source_start {
+            break;
+        }
+        if source_line_no < change.source_end() {
+            return None;
+        }
+        delta += change.delta();
+    }
+    Some((source_line_no as isize + delta) as usize)
+}
+
+/// Source line number of a target line, unless a change adds it
+pub(crate) fn to_source(changes: &[Change], target_line_no: usize) -> Option<usize> {
+    let mut delta = 0isize;
+    for change in changes {
+        let target_start = (change.source_start as isize + delta) as usize;
+        if target_line_no < target_start {
+            break;
+        }
+        if target_line_no < target_start + change.added.len() {
+            return None;
+        }
+        delta += change.delta();
+    }
+    Some((target_line_no as isize - delta) as usize)
+}
+
+/// Build hunks for changes, with up to `context` lines of known source around them
+///
+/// Changes closer than twice the context are merged into a single hunk when the lines
+/// between them are known. A no newline marker follows the context line `no_newline`.
+pub(crate) fn hunks(
+    changes: &[Change],
+    known: &BTreeMap<usize, String>,
+    no_newline: Option<usize>,
+    context: usize,
+) -> Vec<Hunk> {
+    let marker = || Line::new(NO_NEWLINE, LineType::NoNewlineMarker);
+    let context_line = |n: usize, lines: &mut Vec<Line>| {
+        lines.push(Line::new(known[&n].as_str(), LineType::Context));
+        if no_newline == Some(n) {
+            lines.push(marker());
+        }
+    };
+    let mut hunks = vec![];
+    let mut delta = 0isize;
+    let mut previous_end = 1;
+    let mut i = 0;
+    while i < changes.len() {
+        let mut start = changes[i].source_start;
+        while start > previous_end
+            && changes[i].source_start - start < context
+            && known.contains_key(&(start - 1))
+        {
+            start -= 1;
+        }
+
+        let mut j = i;
+        while j + 1 < changes.len() {
+            let end = changes[j].source_end();
+            let next = changes[j + 1].source_start;
+            if next - end > 2 * context || !(end..next).all(|n| known.contains_key(&n)) {
+                break;
+            }
+            j += 1;
+        }
+        let end = changes[j].source_end();
+        let mut stop = end;
+        while stop - end < context && known.contains_key(&stop) {
+            stop += 1;
+        }
+
+        let target_first = (start as isize + delta) as usize;
+        let mut lines = vec![];
+        let mut source_line_no = start;
+        for change in &changes[i..=j] {
+            for n in source_line_no..change.source_start {
+                context_line(n, &mut lines);
+            }
+            for value in &change.removed {
+                lines.push(Line::new(value.as_str(), LineType::Removed));
+            }
+            if change.removed_no_newline {
+                lines.push(marker());
+            }
+            for value in &change.added {
+                lines.push(Line::new(value.as_str(), LineType::Added));
+            }
+            if change.added_no_newline {
+                lines.push(marker());
+            }
+            source_line_no = change.source_end();
+            delta += change.delta();
+        }
+        for n in source_line_no..stop {
+            context_line(n, &mut lines);
+        }
+        hunks.push(Hunk::from_lines(start, target_first, "", lines));
+
+        previous_end = stop;
+        i = j + 1;
+    }
+    hunks
+}
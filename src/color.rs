@@ -0,0 +1,280 @@
+//! Printing patch sets with the colours of `git diff --color`
+use std::fmt;
+
+use crate::{Hunk, Line, PatchSet, PatchedFile};
+
+/// Escape sequences colouring each part of a diff
+///
+/// The default palette is the one of git, and intraline highlights are those of its
+/// `diff-highlight` script.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    /// File headers, like `color.diff.meta`
+    pub meta: String,
+    /// Hunk header ranges, like `color.diff.frag`
+    pub frag: String,
+    /// Hunk section headers, like `color.diff.func`
+    pub func: String,
+    /// Context lines, like `color.diff.context`
+    pub context: String,
+    /// Removed lines, like `color.diff.old`
+    pub old: String,
+    /// Added lines, like `color.diff.new`
+    pub new: String,
+    /// Whitespace errors of added lines, like `color.diff.whitespace`
+    pub whitespace: String,
+    /// Changed part of a removed line
+    pub old_highlight: String,
+    /// Changed part of an added line
+    pub new_highlight: String,
+    /// End of the changed part of a line
+    pub highlight_reset: String,
+    /// End of any colour
+    pub reset: String,
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette {
+            meta: "\x1b[1m".to_owned(),
+            frag: "\x1b[36m".to_owned(),
+            func: String::new(),
+            context: String::new(),
+            old: "\x1b[31m".to_owned(),
+            new: "\x1b[32m".to_owned(),
+            whitespace: "\x1b[41m".to_owned(),
+            old_highlight: "\x1b[7m".to_owned(),
+            new_highlight: "\x1b[7m".to_owned(),
+            highlight_reset: "\x1b[27m".to_owned(),
+            reset: "\x1b[m".to_owned(),
+        }
+    }
+}
+
+/// A patch set printed like `git diff --color`
+///
+/// The text is the one of `PatchSet::to_git_string`, with escape sequences from a `Palette`.
+#[derive(Debug, Clone)]
+pub struct Colored<'a> {
+    patch: &'a PatchSet,
+    palette: Palette,
+    intraline: bool,
+    whitespace_errors: bool,
+}
+
+impl<'a> Colored<'a> {
+    /// Initialize a new Colored printing `patch` with the default palette
+    pub fn new(patch: &'a PatchSet) -> Colored<'a> {
+        Colored {
+            patch,
+            palette: Palette::default(),
+            intraline: false,
+            whitespace_errors: true,
+        }
+    }
+
+    /// Colours to print with
+    pub fn palette(mut self, palette: Palette) -> Colored<'a> {
+        self.palette = palette;
+        self
+    }
+
+    /// Highlight the changed part of removed lines followed by as many added lines
+    pub fn intraline(mut self, intraline: bool) -> Colored<'a> {
+        self.intraline = intraline;
+        self
+    }
+
+    /// Highlight trailing whitespace and spaces before tabs in added lines, on by default
+    pub fn whitespace_errors(mut self, whitespace_errors: bool) -> Colored<'a> {
+        self.whitespace_errors = whitespace_errors;
+        self
+    }
+
+    fn fmt_file(&self, f: &mut fmt::Formatter, file: &PatchedFile) -> fmt::Result {
+        let p = &self.palette;
+        let (header, binary) = file.git_header();
+        for line in &header {
+            writeln!(f, "{}{}{}", p.meta, line, p.reset)?;
+        }
+        if let Some(line) = binary {
+            writeln!(f, "{}", line)?;
+        }
+        for hunk in file.hunks() {
+            self.fmt_hunk(f, hunk)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_hunk(&self, f: &mut fmt::Formatter, hunk: &Hunk) -> fmt::Result {
+        let p = &self.palette;
+        write!(f, "{}{}{}", p.frag, hunk.git_ranges(), p.reset)?;
+        if !hunk.section_header.is_empty() {
+            write!(
+                f,
+                "{} {}{}{}{}",
+                p.context, p.reset, p.func, hunk.section_header, p.reset
+            )?;
+        }
+        writeln!(f)?;
+
+        let lines = hunk.lines();
+        let highlights = if self.intraline {
+            highlights(lines)
+        } else {
+            vec![None; lines.len()]
+        };
+        for (line, highlight) in lines.iter().zip(highlights) {
+            self.fmt_line(f, line, highlight)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_line(
+        &self,
+        f: &mut fmt::Formatter,
+        line: &Line,
+        highlight: Option<(usize, usize)>,
+    ) -> fmt::Result {
+        let p = &self.palette;
+        let (set, marker) = if line.is_added() {
+            (&p.new, &p.new_highlight)
+        } else if line.is_removed() {
+            (&p.old, &p.old_highlight)
+        } else {
+            (&p.context, &p.context)
+        };
+        if let Some((start, end)) = highlight {
+            let value = &line.value;
+            return write!(
+                f,
+                "{}{}{}{}{}{}{}{}",
+                set,
+                line.line_type,
+                &value[..start],
+                marker,
+                &value[start..end],
+                p.highlight_reset,
+                &value[end..],
+                p.reset
+            );
+        }
+        if line.is_added() && self.whitespace_errors {
+            write!(f, "{}{}{}", set, line.line_type, p.reset)?;
+            return self.fmt_whitespace_errors(f, &line.value);
+        }
+        write!(f, "{}{}{}{}", set, line.line_type, line.value, p.reset)
+    }
+
+    /// Print an added line like git does with its default whitespace rules
+    fn fmt_whitespace_errors(&self, f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+        let p = &self.palette;
+        let trailing = value.trim_end_matches(is_git_space).len();
+
+        // spaces before a tab in the indentation are errors
+        let mut written = 0;
+        for (i, c) in value[..trailing].char_indices() {
+            match c {
+                ' ' => continue,
+                '\t' if written < i => {
+                    write!(f, "{}{}{}\t", p.whitespace, &value[written..i], p.reset)?
+                }
+                '\t' => f.write_str(&value[written..=i])?,
+                _ => break,
+            }
+            written = i + 1;
+        }
+
+        if written < trailing {
+            write!(f, "{}{}{}", p.new, &value[written..trailing], p.reset)?;
+        }
+        if trailing < value.len() {
+            write!(f, "{}{}{}", p.whitespace, &value[trailing..], p.reset)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Colored<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for file in self.patch.files() {
+            self.fmt_file(f, file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whitespace as git sees it
+fn is_git_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Changed parts of the values of hunk lines, pairing removed lines with as many added lines
+fn highlights(lines: &[Line]) -> Vec<Option<(usize, usize)>> {
+    let mut highlights = vec![None; lines.len()];
+    let mut i = 0;
+    while i < lines.len() {
+        let removed = i;
+        while i < lines.len() && lines[i].is_removed() {
+            i += 1;
+        }
+        let added = i;
+        while i < lines.len() && lines[i].is_added() {
+            i += 1;
+        }
+        if added - removed == i - added {
+            for k in 0..added - removed {
+                let (old, new) = (removed + k, added + k);
+                if let Some((o, n)) = changed_parts(&lines[old].value, &lines[new].value) {
+                    highlights[old] = Some(o);
+                    highlights[new] = Some(n);
+                }
+            }
+        }
+        if i == removed {
+            i += 1;
+        }
+    }
+    highlights
+}
+
+/// Byte ranges of `old` and `new` between their common prefix and suffix, if they have any
+fn changed_parts(old: &str, new: &str) -> Option<((usize, usize), (usize, usize))> {
+    let prefix: usize = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    let suffix: usize = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    if prefix == 0 && suffix == 0 {
+        return None;
+    }
+    Some(((prefix, old.len() - suffix), (prefix, new.len() - suffix)))
+}
+
+impl PatchSet {
+    /// This patch set printed like `git diff --color`
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-old line\n+new line\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let text = patch.colored().intraline(true).to_string();
+    /// assert!(text.ends_with(
+    ///     "\x1b[31m-\x1b[7mold\x1b[27m line\x1b[m\n\x1b[32m+\x1b[7mnew\x1b[27m line\x1b[m\n"
+    /// ));
+    /// ```
+    pub fn colored(&self) -> Colored<'_> {
+        Colored::new(self)
+    }
+}
@@ -0,0 +1,172 @@
+//! Reordering of two consecutive patches
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::change::{self, Change, CONTEXT};
+use crate::{Error, Hunk, PatchSet, PatchedFile, Result};
+
+/// Changes of two patches preventing them from being reordered
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// Path of the file changed by both patches
+    pub path: String,
+    /// Index of the conflicting hunk of the first patch, `None` when the whole file conflicts
+    pub first_hunk: Option<usize>,
+    /// Index of the conflicting hunk of the second patch, `None` when the whole file conflicts
+    pub second_hunk: Option<usize>,
+    /// First and last line of the intermediate file touched by both hunks
+    pub lines: Option<(usize, usize)>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.first_hunk, self.second_hunk, self.lines) {
+            (Some(first), Some(second), Some((start, end))) => write!(
+                f,
+                "{}: hunk #{} of the first patch and hunk #{} of the second patch touch lines {}-{}",
+                self.path,
+                first + 1,
+                second + 1,
+                start,
+                end
+            ),
+            _ => write!(f, "{}: both patches create, delete or rename the file", self.path),
+        }
+    }
+}
+
+/// File name without its `a/` or `b/` prefix
+fn name(file: &str) -> Option<&str> {
+    if file == "/dev/null" {
+        return None;
+    }
+    Some(file.trim_start_matches("a/").trim_start_matches("b/"))
+}
+
+/// Is this file only modified in place
+fn is_in_place(file: &PatchedFile) -> bool {
+    name(&file.source_file).is_some() && name(&file.source_file) == name(&file.target_file)
+}
+
+/// Changes of hunks with the index of the hunk they come from
+fn changes(hunks: &[Hunk]) -> Vec<(usize, Change)> {
+    hunks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, h)| {
+            change::changes(std::slice::from_ref(h))
+                .into_iter()
+                .map(move |c| (i, c))
+        })
+        .collect()
+}
+
+/// Commute the changes of two consecutive versions of a file
+fn commute(first: &PatchedFile, second: &PatchedFile) -> Result<(PatchedFile, PatchedFile)> {
+    let first_changes = changes(&first.hunks);
+    let second_changes = changes(&second.hunks);
+    let a: Vec<Change> = first_changes.iter().map(|(_, c)| c.clone()).collect();
+    let b: Vec<Change> = second_changes.iter().map(|(_, c)| c.clone()).collect();
+
+    // changes may only be reordered when they neither overlap nor touch
+    let mut delta = 0isize;
+    for (first_hunk, change) in &first_changes {
+        let start = (change.source_start as isize + delta) as usize;
+        let end = start + change.added.len();
+        delta += change.delta();
+        for (second_hunk, other) in &second_changes {
+            if other.source_start <= end && start <= other.source_end() {
+                let first_line = start.min(other.source_start);
+                let last_line = end.max(other.source_end()).max(first_line + 1) - 1;
+                return Err(Error::Conflict(Conflict {
+                    path: second.path(),
+                    first_hunk: Some(*first_hunk),
+                    second_hunk: Some(*second_hunk),
+                    lines: Some((first_line, last_line)),
+                }));
+            }
+        }
+    }
+
+    let second_commuted: Vec<Change> = b
+        .iter()
+        .map(|c| Change {
+            source_start: change::to_source(&a, c.source_start).unwrap(),
+            ..c.clone()
+        })
+        .collect();
+    let first_commuted: Vec<Change> = a
+        .iter()
+        .map(|c| Change {
+            source_start: change::to_target(&second_commuted, c.source_start).unwrap(),
+            ..c.clone()
+        })
+        .collect();
+
+    // original content from the first patch and what the second one knows of it
+    let mut base = change::source_lines(&first.hunks);
+    for (line_no, value) in change::source_lines(&second.hunks) {
+        if let Some(line_no) = change::to_source(&a, line_no) {
+            base.entry(line_no).or_insert(value);
+        }
+    }
+    // content between both commuted patches
+    let mut middle = change::target_lines(&second.hunks)
+        .into_iter()
+        .filter_map(|(line_no, value)| {
+            change::to_source(&first_commuted, line_no).map(|line_no| (line_no, value))
+        })
+        .collect::<BTreeMap<_, _>>();
+    for (line_no, value) in &base {
+        if let Some(line_no) = change::to_target(&second_commuted, *line_no) {
+            middle.entry(line_no).or_insert_with(|| value.clone());
+        }
+    }
+
+    // last lines without newline, in the same numbering
+    let base_no_newline = change::source_no_newline(&first.hunks).or_else(|| {
+        change::source_no_newline(&second.hunks).and_then(|n| change::to_source(&a, n))
+    });
+    let middle_no_newline = change::target_no_newline(&second.hunks)
+        .and_then(|n| change::to_source(&first_commuted, n))
+        .or_else(|| base_no_newline.and_then(|n| change::to_target(&second_commuted, n)));
+
+    let mut second_file = second.clone();
+    second_file.hunks = change::hunks(&second_commuted, &base, base_no_newline, CONTEXT);
+    let mut first_file = first.clone();
+    first_file.hunks = change::hunks(&first_commuted, &middle, middle_no_newline, CONTEXT);
+    Ok((second_file, first_file))
+}
+
+impl PatchSet {
+    /// Reorder this patch set with `next`, which applies on top of it
+    ///
+    /// Returns `next` rebased to apply first, followed by this patch set rebased to apply on
+    /// top of it, together producing the same result. Fails with a `Conflict` when both
+    /// change, or touch, the same lines of a file.
+    pub fn commute(&self, next: &PatchSet) -> Result<(PatchSet, PatchSet)> {
+        let mut first = self.clone();
+        let mut second = next.clone();
+        for (i, file) in self.files.iter().enumerate() {
+            let names = [name(&file.source_file), name(&file.target_file)];
+            for (j, other) in next.files.iter().enumerate() {
+                let other_names = [name(&other.source_file), name(&other.target_file)];
+                if !names.iter().any(|n| n.is_some() && other_names.contains(n)) {
+                    continue;
+                }
+                if !is_in_place(file) || !is_in_place(other) {
+                    return Err(Error::Conflict(Conflict {
+                        path: other.path(),
+                        first_hunk: None,
+                        second_hunk: None,
+                        lines: None,
+                    }));
+                }
+                let (second_file, first_file) = commute(file, other)?;
+                second.files[j] = second_file;
+                first.files[i] = first_file;
+            }
+        }
+        Ok((second, first))
+    }
+}
@@ -0,0 +1,116 @@
+//! Changing the amount of context lines of a patch
+use std::collections::BTreeMap;
+
+use crate::change;
+use crate::{Error, Hunk, PatchSet, PatchedFile, Result};
+
+/// Source line of the first change of a hunk
+fn first_change(hunk: &Hunk) -> usize {
+    let mut source_line_no = hunk.source_first();
+    for line in hunk.lines() {
+        if line.is_added() || line.is_removed() {
+            break;
+        }
+        if line.is_context() {
+            source_line_no += 1;
+        }
+    }
+    source_line_no
+}
+
+impl PatchedFile {
+    /// Rebuild hunks with `context` lines around changes, using the known source lines
+    fn rebuild(
+        &self,
+        known: &BTreeMap<usize, String>,
+        no_newline: Option<usize>,
+        context: usize,
+    ) -> PatchedFile {
+        let changes = change::changes(&self.hunks);
+        let mut hunks = change::hunks(&changes, known, no_newline, context);
+        // keep the section header of the hunk each change came from
+        for hunk in &mut hunks {
+            let first = first_change(hunk);
+            if let Some(original) = self.hunks.iter().rev().find(|h| h.source_first() <= first) {
+                hunk.section_header = original.section_header.clone();
+            }
+        }
+        let mut file = self.clone();
+        file.hunks = hunks;
+        file
+    }
+
+    /// This file with at most `context` lines of context around changes
+    ///
+    /// Hunks are split where changes are more than twice the context apart. Context can
+    /// only be reduced, as lines outside of the hunks are unknown.
+    pub fn with_context(&self, context: usize) -> PatchedFile {
+        self.rebuild(
+            &change::source_lines(&self.hunks),
+            change::source_no_newline(&self.hunks),
+            context,
+        )
+    }
+
+    /// This file with `context` lines of context around changes, taken from the source file
+    ///
+    /// Context is reduced or expanded, and hunks closer than twice the context are merged.
+    /// Fails when the content of the hunks does not match `source`.
+    pub fn with_context_from_source(&self, context: usize, source: &str) -> Result<PatchedFile> {
+        let known: BTreeMap<usize, String> = source
+            .lines()
+            .enumerate()
+            .map(|(n, l)| (n + 1, l.to_owned()))
+            .collect();
+        for (line_no, value) in change::source_lines(&self.hunks) {
+            match known.get(&line_no) {
+                Some(source_value) if *source_value == value => {}
+                source_value => {
+                    return Err(Error::Mismatch(format!(
+                        "{}: line {} is {:?} in the patch but {:?} in the source",
+                        self.path(),
+                        line_no,
+                        value,
+                        source_value.map_or("", |v| v.as_str())
+                    )))
+                }
+            }
+        }
+        // the last line of a source without final newline is kept as context without it too
+        let no_newline = if source.ends_with('\n') {
+            None
+        } else {
+            known.keys().next_back().cloned()
+        };
+        Ok(self.rebuild(&known, no_newline, context))
+    }
+}
+
+impl PatchSet {
+    /// This patch set with at most `context` lines of context around changes
+    pub fn with_context(&self, context: usize) -> PatchSet {
+        let mut patch = self.clone();
+        patch.files = self.files.iter().map(|f| f.with_context(context)).collect();
+        patch
+    }
+
+    /// This patch set with `context` lines of context around changes, taken from source files
+    ///
+    /// `source` returns the content of the source file of a patched file, or `None` to only
+    /// reduce the context of that file.
+    pub fn with_context_from_source<F>(&self, context: usize, mut source: F) -> Result<PatchSet>
+    where
+        F: FnMut(&PatchedFile) -> Option<String>,
+    {
+        let mut patch = self.clone();
+        patch.files = self
+            .files
+            .iter()
+            .map(|f| match source(f) {
+                Some(content) => f.with_context_from_source(context, &content),
+                None => Ok(f.with_context(context)),
+            })
+            .collect::<Result<Vec<PatchedFile>>>()?;
+        Ok(patch)
+    }
+}
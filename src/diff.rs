@@ -0,0 +1,100 @@
+//! Myers line diff, used to compute hunks between in-memory contents
+use std::ops::Range;
+
+/// Compute the regions that differ between `old` and `new`
+///
+/// Each region is a pair of index ranges, replacing `old[region.0]` with `new[region.1]`.
+pub(crate) fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<(Range<usize>, Range<usize>)> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let mut regions: Vec<(Range<usize>, Range<usize>)> = vec![];
+    let mut push = |x: usize, y: usize, deleted: bool| {
+        let (x, y) = (x + prefix, y + prefix);
+        if let Some(last) = regions.last_mut() {
+            // edits are produced back to front
+            if deleted && last.0.start == x + 1 && last.1.start == y {
+                last.0.start = x;
+                return;
+            }
+            if !deleted && last.0.start == x && last.1.start == y + 1 {
+                last.1.start = y;
+                return;
+            }
+        }
+        if deleted {
+            regions.push((x..x + 1, y..y));
+        } else {
+            regions.push((x..x, y..y + 1));
+        }
+    };
+
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = n + m;
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let mut trace = vec![];
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let v = &trace[d as usize];
+        let k = x - y;
+        let idx = (k + offset) as usize;
+        let prev_k = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                push(x as usize, (y - 1) as usize, false);
+            } else {
+                push((x - 1) as usize, y as usize, true);
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    regions.reverse();
+    regions
+}
@@ -0,0 +1,165 @@
+//! Selecting files of a patch set by name, like patchutils' `filterdiff`
+use crate::paths::strip_components;
+use crate::{PatchSet, PatchedFile};
+
+/// File name patterns are matched against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchOn {
+    /// Source file name
+    Source,
+    /// Target file name
+    Target,
+    /// Patched file relative path, see `PatchedFile::path`
+    #[default]
+    Path,
+}
+
+/// Include and exclude shell patterns selecting files of a patch set
+///
+/// A file is selected when it matches any include pattern, or there are none, and matches
+/// no exclude pattern. As with `fnmatch`, `*` also matches `/`.
+///
+/// ```
+/// use unidiff::Filter;
+///
+/// let filter = Filter::new().include("src/*").exclude("*.generated.rs").strip(1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Filter {
+    includes: Vec<String>,
+    excludes: Vec<String>,
+    strip: usize,
+    match_on: MatchOn,
+}
+
+impl Filter {
+    /// Initialize a new Filter selecting every file
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    /// Select files matching `pattern`
+    pub fn include<T: Into<String>>(mut self, pattern: T) -> Filter {
+        self.includes.push(pattern.into());
+        self
+    }
+
+    /// Leave out files matching `pattern`
+    pub fn exclude<T: Into<String>>(mut self, pattern: T) -> Filter {
+        self.excludes.push(pattern.into());
+        self
+    }
+
+    /// Strip `n` leading path components before matching, like `patch -p`
+    pub fn strip(mut self, n: usize) -> Filter {
+        self.strip = n;
+        self
+    }
+
+    /// Match patterns against this file name
+    pub fn match_on(mut self, name: MatchOn) -> Filter {
+        self.match_on = name;
+        self
+    }
+
+    /// Is this file selected
+    pub fn matches(&self, file: &PatchedFile) -> bool {
+        let path = file.path();
+        let name = match self.match_on {
+            MatchOn::Source => &file.source_file,
+            MatchOn::Target => &file.target_file,
+            MatchOn::Path => &path,
+        };
+        let name = match strip_components(name, self.strip) {
+            Some(name) => name,
+            None => return false,
+        };
+        (self.includes.is_empty() || self.includes.iter().any(|p| fnmatch(p, name)))
+            && !self.excludes.iter().any(|p| fnmatch(p, name))
+    }
+}
+
+impl PatchSet {
+    /// Patch set with only the files selected by `filter`
+    pub fn filter(&self, filter: &Filter) -> PatchSet {
+        let mut patch = self.clone();
+        patch.files.retain(|f| filter.matches(f));
+        patch
+    }
+}
+
+/// Does `name` match the shell pattern
+///
+/// Stars are matched greedily, going back to the last star seen when the rest fails, which
+/// takes at most the product of both lengths.
+fn fnmatch(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // pattern index after the last star, and the name index it matches up to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, n));
+            continue;
+        }
+        if let Some(len) = match_one(&pattern[p..], name[n]) {
+            p += len;
+            n += 1;
+            continue;
+        }
+        match star {
+            Some((star_p, star_n)) => {
+                p = star_p;
+                n = star_n + 1;
+                star = Some((star_p, n));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Length of the pattern element starting `pattern` when it matches `c`, stars excluded
+fn match_one(pattern: &[char], c: char) -> Option<usize> {
+    let (matches, len) = match *pattern.first()? {
+        '?' => (true, 1),
+        '[' => match class(&pattern[1..]) {
+            Some((matches, len)) => (matches(c), len + 1),
+            None => (c == '[', 1),
+        },
+        '\\' if pattern.len() > 1 => (pattern[1] == c, 2),
+        p => (p == c, 1),
+    };
+    if matches {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// Parse a bracket expression following `[`, returning its matcher and length
+fn class(pattern: &[char]) -> Option<(impl Fn(char) -> bool, usize)> {
+    let negated = matches!(pattern.first(), Some('!') | Some('^'));
+    let mut i = negated as usize;
+    let mut ranges = vec![];
+    // a closing bracket first in the class is taken literally
+    let mut first = true;
+    loop {
+        let c = *pattern.get(i)?;
+        if c == ']' && !first {
+            break;
+        }
+        first = false;
+        if pattern.get(i + 1) == Some(&'-') && matches!(pattern.get(i + 2), Some(&e) if e != ']') {
+            ranges.push((c, pattern[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+    let matcher = move |c: char| ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != negated;
+    Some((matcher, i + 1))
+}
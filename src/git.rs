@@ -0,0 +1,203 @@
+//! Writing patches in the format of `git diff`
+use crate::paths::names;
+use crate::{ExtendedHeader, Hunk, PatchSet, PatchedFile};
+
+/// Hunk range, with the length left out when it is 1 like git does
+fn range(first: usize, length: usize) -> String {
+    if length == 1 {
+        first.to_string()
+    } else {
+        format!("{},{}", first, length)
+    }
+}
+
+impl Hunk {
+    /// Ranges of the header of this hunk, as written by `git diff`
+    pub(crate) fn git_ranges(&self) -> String {
+        format!(
+            "@@ -{} +{} @@",
+            range(self.source_start, self.source_length),
+            range(self.target_start, self.target_length)
+        )
+    }
+
+    /// This hunk as written by `git diff`, ending with a newline
+    pub fn to_git_string(&self) -> String {
+        let mut text = self.git_ranges();
+        if !self.section_header.is_empty() {
+            text.push(' ');
+            text.push_str(&self.section_header);
+        }
+        text.push('\n');
+        for line in &self.lines {
+            text.push_str(&line.to_string());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl PatchedFile {
+    /// Header lines of this file as written by `git diff`, and the line of a binary file
+    pub(crate) fn git_header(&self) -> (Vec<String>, Option<String>) {
+        let (source, target) = names(self);
+        let is_added = source == "/dev/null"
+            || self
+                .extended_headers
+                .iter()
+                .any(|h| matches!(h, ExtendedHeader::NewFileMode(..)));
+        let is_removed = target == "/dev/null"
+            || self
+                .extended_headers
+                .iter()
+                .any(|h| matches!(h, ExtendedHeader::DeletedFileMode(..)));
+        let source_path = if source == "/dev/null" {
+            target
+        } else {
+            source
+        };
+        let target_path = if target == "/dev/null" {
+            source
+        } else {
+            target
+        };
+        let (source_path, target_path) = (
+            source_path.trim_start_matches('/'),
+            target_path.trim_start_matches('/'),
+        );
+        // different names only make a rename or a copy when git headers tell so
+        let is_moved = self.extended_headers.iter().any(|h| {
+            matches!(
+                h,
+                ExtendedHeader::RenameFrom(..)
+                    | ExtendedHeader::RenameTo(..)
+                    | ExtendedHeader::CopyFrom(..)
+                    | ExtendedHeader::CopyTo(..)
+                    | ExtendedHeader::SimilarityIndex(..)
+            )
+        });
+        let source_path = if is_moved || is_added || is_removed {
+            source_path
+        } else {
+            target_path
+        };
+
+        // headers of this file of a kind, or a default one
+        let find = |kind: fn(&ExtendedHeader) -> bool, default: Option<ExtendedHeader>| {
+            let found: Vec<ExtendedHeader> = self
+                .extended_headers
+                .iter()
+                .filter(|h| kind(h))
+                .cloned()
+                .collect();
+            if found.is_empty() {
+                default.into_iter().collect()
+            } else {
+                found
+            }
+        };
+        let mut headers: Vec<ExtendedHeader> = vec![];
+        if is_added {
+            headers.extend(find(
+                |h| matches!(h, ExtendedHeader::NewFileMode(..)),
+                Some(ExtendedHeader::NewFileMode("100644".to_owned())),
+            ));
+        } else if is_removed {
+            headers.extend(find(
+                |h| matches!(h, ExtendedHeader::DeletedFileMode(..)),
+                Some(ExtendedHeader::DeletedFileMode("100644".to_owned())),
+            ));
+        } else {
+            headers.extend(find(|h| matches!(h, ExtendedHeader::OldMode(..)), None));
+            headers.extend(find(|h| matches!(h, ExtendedHeader::NewMode(..)), None));
+        }
+        if !is_added && !is_removed && source_path != target_path {
+            let is_copy = self
+                .extended_headers
+                .iter()
+                .any(|h| matches!(h, ExtendedHeader::CopyFrom(..) | ExtendedHeader::CopyTo(..)));
+            headers.extend(find(
+                |h| matches!(h, ExtendedHeader::SimilarityIndex(..)),
+                None,
+            ));
+            headers.extend(find(
+                |h| matches!(h, ExtendedHeader::DissimilarityIndex(..)),
+                None,
+            ));
+            if is_copy {
+                headers.push(ExtendedHeader::CopyFrom(source_path.to_owned()));
+                headers.push(ExtendedHeader::CopyTo(target_path.to_owned()));
+            } else {
+                headers.push(ExtendedHeader::RenameFrom(source_path.to_owned()));
+                headers.push(ExtendedHeader::RenameTo(target_path.to_owned()));
+            }
+        }
+        headers.extend(find(|h| matches!(h, ExtendedHeader::Index(..)), None));
+
+        let mut lines = vec![format!("diff --git a/{} b/{}", source_path, target_path)];
+        lines.extend(headers.iter().map(|h| h.to_string()));
+        let source_name = if is_added {
+            "/dev/null".to_owned()
+        } else {
+            format!("a/{}", source_path)
+        };
+        let target_name = if is_removed {
+            "/dev/null".to_owned()
+        } else {
+            format!("b/{}", target_path)
+        };
+        if self.hunks.is_empty() {
+            let binary = if self.binary {
+                Some(format!(
+                    "Binary files {} and {} differ",
+                    source_name, target_name
+                ))
+            } else {
+                None
+            };
+            return (lines, binary);
+        }
+        lines.push(format!("--- {}", source_name));
+        lines.push(format!("+++ {}", target_name));
+        (lines, None)
+    }
+
+    /// This file as written by `git diff`, ending with a newline
+    ///
+    /// File names get the `a/` and `b/` prefixes, and the missing side of added and removed
+    /// files is `/dev/null`. Files are only renamed or copied when their git headers tell so.
+    /// Other files with different names, like `foo.c.orig` and `foo.c`, are patched in place
+    /// under the target name. Modes and `index` lines come from the git headers of this file, new
+    /// files default to mode 100644.
+    pub fn to_git_string(&self) -> String {
+        let (header, binary) = self.git_header();
+        let mut text = String::new();
+        for line in header.iter().chain(binary.iter()) {
+            text.push_str(line);
+            text.push('\n');
+        }
+        for hunk in &self.hunks {
+            text.push_str(&hunk.to_git_string());
+        }
+        text
+    }
+}
+
+impl PatchSet {
+    /// This patch set as written by `git diff`, for `git apply`
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- old.txt\n+++ new.txt\n@@ -1,1 +1,1 @@ fn\n-a\n+b\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     "diff --git a/new.txt b/new.txt\n--- a/new.txt\n+++ b/new.txt\n@@ -1 +1 @@ fn\n-a\n+b\n",
+    ///     patch.to_git_string()
+    /// );
+    /// ```
+    pub fn to_git_string(&self) -> String {
+        self.files.iter().map(|f| f.to_git_string()).collect()
+    }
+}
@@ -0,0 +1,117 @@
+//! Searching lines of a patch set, like patchutils' `grepdiff`
+use regex::Regex;
+
+use crate::{Hunk, Line, PatchSet, PatchedFile};
+
+/// Regex search over the lines of a patch set
+///
+/// By default added and removed lines are searched, not context lines.
+///
+/// ```
+/// use regex::Regex;
+/// use unidiff::Grep;
+///
+/// let grep = Grep::new(Regex::new(r"\bunsafe\b").unwrap()).removed(false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Grep {
+    regex: Regex,
+    added: bool,
+    removed: bool,
+    context: bool,
+}
+
+impl Grep {
+    /// Initialize a new Grep searching added and removed lines for `regex`
+    pub fn new(regex: Regex) -> Grep {
+        Grep {
+            regex,
+            added: true,
+            removed: true,
+            context: false,
+        }
+    }
+
+    /// Set whether to search added lines
+    pub fn added(mut self, search: bool) -> Grep {
+        self.added = search;
+        self
+    }
+
+    /// Set whether to search removed lines
+    pub fn removed(mut self, search: bool) -> Grep {
+        self.removed = search;
+        self
+    }
+
+    /// Set whether to search context lines
+    pub fn context(mut self, search: bool) -> Grep {
+        self.context = search;
+        self
+    }
+
+    /// Does this line match
+    pub fn is_match(&self, line: &Line) -> bool {
+        ((self.added && line.is_added())
+            || (self.removed && line.is_removed())
+            || (self.context && line.is_context()))
+            && self.regex.is_match(&line.value)
+    }
+
+    /// Does any line of this hunk match
+    pub fn is_hunk_match(&self, hunk: &Hunk) -> bool {
+        hunk.lines().iter().any(|l| self.is_match(l))
+    }
+
+    /// Does any line of this file match
+    pub fn is_file_match(&self, file: &PatchedFile) -> bool {
+        file.hunks().iter().any(|h| self.is_hunk_match(h))
+    }
+}
+
+/// A line matched by a `Grep`, with the file and hunk it belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrepMatch<'a> {
+    /// File of the matching line
+    pub file: &'a PatchedFile,
+    /// Hunk of the matching line
+    pub hunk: &'a Hunk,
+    /// Matching line
+    pub line: &'a Line,
+}
+
+impl PatchSet {
+    /// Lines matching `grep`, in patch order
+    pub fn grep<'a>(&'a self, grep: &Grep) -> Vec<GrepMatch<'a>> {
+        let mut matches = vec![];
+        for file in &self.files {
+            for hunk in file.hunks() {
+                for line in hunk.lines() {
+                    if grep.is_match(line) {
+                        matches.push(GrepMatch { file, hunk, line });
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// Patch set with only the files containing a match, like `grepdiff --output-matching=file`
+    pub fn grep_files(&self, grep: &Grep) -> PatchSet {
+        let mut patch = self.clone();
+        patch.files.retain(|f| grep.is_file_match(f));
+        patch
+    }
+
+    /// Patch set with only the hunks containing a match, like `grepdiff --output-matching=hunk`
+    ///
+    /// Hunk starts are recounted so that the remaining hunks still apply.
+    pub fn grep_hunks(&self, grep: &Grep) -> PatchSet {
+        let mut patch = self.grep_files(grep);
+        for file in &mut patch.files {
+            file.hunks.retain(|h| grep.is_hunk_match(h));
+            file.recount();
+        }
+        patch
+    }
+}
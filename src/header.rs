@@ -0,0 +1,121 @@
+//! Git extended header lines, between `diff --git` and the file names
+use std::fmt;
+
+/// A git extended header line of a patched file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ExtendedHeader {
+    /// `old mode <mode>`
+    OldMode(String),
+    /// `new mode <mode>`
+    NewMode(String),
+    /// `deleted file mode <mode>`
+    DeletedFileMode(String),
+    /// `new file mode <mode>`
+    NewFileMode(String),
+    /// `copy from <path>`
+    CopyFrom(String),
+    /// `copy to <path>`
+    CopyTo(String),
+    /// `rename from <path>`
+    RenameFrom(String),
+    /// `rename to <path>`
+    RenameTo(String),
+    /// `similarity index <number>%`
+    SimilarityIndex(usize),
+    /// `dissimilarity index <number>%`
+    DissimilarityIndex(usize),
+    /// `index <hash>..<hash>[ <mode>]`
+    Index(String),
+}
+
+impl ExtendedHeader {
+    /// Parse an extended header line, `None` if it is not one
+    pub fn parse(line: &str) -> Option<ExtendedHeader> {
+        let percent = |value: &str| value.strip_suffix('%').and_then(|v| v.parse().ok());
+        let (name, value) = [
+            "old mode ",
+            "new mode ",
+            "deleted file mode ",
+            "new file mode ",
+            "copy from ",
+            "copy to ",
+            "rename from ",
+            "rename to ",
+            "similarity index ",
+            "dissimilarity index ",
+            "index ",
+        ]
+        .iter()
+        .find_map(|name| line.strip_prefix(name).map(|value| (*name, value)))?;
+        let value = value.trim_end();
+        let header = match name {
+            "old mode " => ExtendedHeader::OldMode(value.to_owned()),
+            "new mode " => ExtendedHeader::NewMode(value.to_owned()),
+            "deleted file mode " => ExtendedHeader::DeletedFileMode(value.to_owned()),
+            "new file mode " => ExtendedHeader::NewFileMode(value.to_owned()),
+            "copy from " => ExtendedHeader::CopyFrom(value.to_owned()),
+            "copy to " => ExtendedHeader::CopyTo(value.to_owned()),
+            "rename from " => ExtendedHeader::RenameFrom(value.to_owned()),
+            "rename to " => ExtendedHeader::RenameTo(value.to_owned()),
+            "similarity index " => ExtendedHeader::SimilarityIndex(percent(value)?),
+            "dissimilarity index " => ExtendedHeader::DissimilarityIndex(percent(value)?),
+            _ => ExtendedHeader::Index(value.to_owned()),
+        };
+        Some(header)
+    }
+
+    /// Header undoing this one, for a reversed file
+    ///
+    /// Copy headers have no reverse and give `None`.
+    pub fn reverse(&self) -> Option<ExtendedHeader> {
+        let header = match *self {
+            ExtendedHeader::OldMode(ref m) => ExtendedHeader::NewMode(m.clone()),
+            ExtendedHeader::NewMode(ref m) => ExtendedHeader::OldMode(m.clone()),
+            ExtendedHeader::DeletedFileMode(ref m) => ExtendedHeader::NewFileMode(m.clone()),
+            ExtendedHeader::NewFileMode(ref m) => ExtendedHeader::DeletedFileMode(m.clone()),
+            ExtendedHeader::CopyFrom(..) | ExtendedHeader::CopyTo(..) => return None,
+            ExtendedHeader::RenameFrom(ref p) => ExtendedHeader::RenameTo(p.clone()),
+            ExtendedHeader::RenameTo(ref p) => ExtendedHeader::RenameFrom(p.clone()),
+            ExtendedHeader::Index(ref index) => {
+                let (hashes, mode) = match index.find(' ') {
+                    Some(i) => index.split_at(i),
+                    None => (index.as_str(), ""),
+                };
+                match hashes.find("..") {
+                    Some(i) => ExtendedHeader::Index(format!(
+                        "{}..{}{}",
+                        &hashes[i + 2..],
+                        &hashes[..i],
+                        mode
+                    )),
+                    None => self.clone(),
+                }
+            }
+            _ => self.clone(),
+        };
+        Some(header)
+    }
+}
+
+impl fmt::Display for ExtendedHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExtendedHeader::OldMode(ref m) => write!(f, "old mode {}", m),
+            ExtendedHeader::NewMode(ref m) => write!(f, "new mode {}", m),
+            ExtendedHeader::DeletedFileMode(ref m) => write!(f, "deleted file mode {}", m),
+            ExtendedHeader::NewFileMode(ref m) => write!(f, "new file mode {}", m),
+            ExtendedHeader::CopyFrom(ref p) => write!(f, "copy from {}", p),
+            ExtendedHeader::CopyTo(ref p) => write!(f, "copy to {}", p),
+            ExtendedHeader::RenameFrom(ref p) => write!(f, "rename from {}", p),
+            ExtendedHeader::RenameTo(ref p) => write!(f, "rename to {}", p),
+            ExtendedHeader::SimilarityIndex(n) => write!(f, "similarity index {}%", n),
+            ExtendedHeader::DissimilarityIndex(n) => write!(f, "dissimilarity index {}%", n),
+            ExtendedHeader::Index(ref index) => write!(f, "index {}", index),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Printing patch sets as self-contained HTML documents
+use std::fmt;
+
+use crate::{FileStat, Hunk, Line, LineType, PatchSet, PatchedFile};
+
+/// Style sheet of the documents, so they need no other file
+const STYLE: &str = "body{font-family:sans-serif;margin:1em}\
+.file{border:1px solid #d0d7de;border-radius:6px;margin-bottom:1em}\
+.file-header{background:#f6f8fa;border-bottom:1px solid #d0d7de;padding:.5em}\
+.file-header a{color:inherit;font-weight:bold;text-decoration:none}\
+.meta{color:#57606a;margin:.5em 0 0}\
+.binary{padding:.5em}\
+table{border-collapse:collapse;font-family:monospace;width:100%}\
+td{padding:0 .5em;vertical-align:top;white-space:pre-wrap;word-break:break-all}\
+td.num{color:#57606a;text-align:right;user-select:none;width:1%;white-space:nowrap}\
+td.num a{color:inherit;text-decoration:none}\
+tr.hunk td{background:#ddf4ff;color:#57606a}\
+.removed{background:#ffebe9}\
+.added{background:#e6ffec}\
+.empty{background:#f6f8fa}\
+.marker{color:#57606a}\
+.split td.code{width:49%}\
+:target{background:#fff8c5}";
+
+/// A patch set printed as an HTML document, in a unified or a split view
+///
+/// Each file has an anchor `f{n}`, counting files from 1, and each line number an anchor
+/// `f{n}-L{line}` on the source side and `f{n}-R{line}` on the target side.
+#[derive(Debug, Clone)]
+pub struct Html<'a> {
+    patch: &'a PatchSet,
+    split: bool,
+    title: String,
+}
+
+impl<'a> Html<'a> {
+    /// Initialize a new Html printing `patch` in the unified view
+    pub fn new(patch: &'a PatchSet) -> Html<'a> {
+        Html {
+            patch,
+            split: false,
+            title: "Diff".to_owned(),
+        }
+    }
+
+    /// Print the source and target side by side instead of one after the other
+    pub fn split(mut self, split: bool) -> Html<'a> {
+        self.split = split;
+        self
+    }
+
+    /// Title of the document
+    pub fn title<T: Into<String>>(mut self, title: T) -> Html<'a> {
+        self.title = title.into();
+        self
+    }
+
+    fn fmt_file(&self, f: &mut fmt::Formatter, n: usize, file: &PatchedFile) -> fmt::Result {
+        let id = format!("f{}", n);
+        writeln!(f, "<div class=\"file\" id=\"{}\">", id)?;
+        write!(
+            f,
+            "<div class=\"file-header\"><a href=\"#{}\">{}</a>",
+            id,
+            escape(&FileStat::new(file).name)
+        )?;
+        if !file.extended_headers().is_empty() {
+            let headers: Vec<String> = file
+                .extended_headers()
+                .iter()
+                .map(|h| escape(&h.to_string()))
+                .collect();
+            write!(f, "<pre class=\"meta\">{}</pre>", headers.join("\n"))?;
+        }
+        writeln!(f, "</div>")?;
+
+        if file.is_binary_file() && file.hunks().is_empty() {
+            writeln!(f, "<div class=\"binary\">Binary file</div>")?;
+        } else {
+            let class = if self.split { "split" } else { "unified" };
+            writeln!(f, "<table class=\"{}\">", class)?;
+            for hunk in file.hunks() {
+                if self.split {
+                    fmt_split_hunk(f, &id, hunk)?;
+                } else {
+                    fmt_unified_hunk(f, &id, hunk)?;
+                }
+            }
+            writeln!(f, "</table>")?;
+        }
+        writeln!(f, "</div>")
+    }
+}
+
+impl<'a> fmt::Display for Html<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, "<html>")?;
+        writeln!(f, "<head>")?;
+        writeln!(f, "<meta charset=\"utf-8\">")?;
+        writeln!(f, "<title>{}</title>", escape(&self.title))?;
+        writeln!(f, "<style>{}</style>", STYLE)?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        for (i, file) in self.patch.files().iter().enumerate() {
+            self.fmt_file(f, i + 1, file)?;
+        }
+        writeln!(f, "</body>")?;
+        writeln!(f, "</html>")
+    }
+}
+
+/// Text with the characters special to HTML escaped
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Class of the row or cells of a line
+fn class(line: &Line) -> &'static str {
+    match line.line_type {
+        LineType::Added => "added",
+        LineType::Removed => "removed",
+        LineType::NoNewlineMarker => "marker",
+        _ => "context",
+    }
+}
+
+/// Cell of the line number of `line` on one side, with an anchor `{id}-{side}{n}`
+fn number(id: &str, side: char, line: &Line) -> String {
+    let line_no = if side == 'L' {
+        line.source_line_no
+    } else {
+        line.target_line_no
+    };
+    match line_no {
+        Some(n) => format!(
+            "<td class=\"num {class}\" id=\"{id}-{side}{n}\"><a href=\"#{id}-{side}{n}\">{n}</a></td>",
+            class = class(line),
+            id = id,
+            side = side,
+            n = n
+        ),
+        None => format!("<td class=\"num {}\"></td>", class(line)),
+    }
+}
+
+/// Cell of the text of a line
+fn code(line: &Line) -> String {
+    format!(
+        "<td class=\"code {}\">{}{}</td>",
+        class(line),
+        line.line_type,
+        escape(&line.value)
+    )
+}
+
+/// Hunk header of the ranges and section of a hunk
+fn hunk_header(hunk: &Hunk) -> String {
+    let mut header = hunk.git_ranges();
+    if !hunk.section_header.is_empty() {
+        header.push(' ');
+        header.push_str(&hunk.section_header);
+    }
+    escape(&header)
+}
+
+fn fmt_unified_hunk(f: &mut fmt::Formatter, id: &str, hunk: &Hunk) -> fmt::Result {
+    writeln!(
+        f,
+        "<tr class=\"hunk\"><td class=\"num\"></td><td class=\"num\"></td>\
+         <td class=\"code\">{}</td></tr>",
+        hunk_header(hunk)
+    )?;
+    for line in hunk.lines() {
+        writeln!(
+            f,
+            "<tr>{}{}{}</tr>",
+            number(id, 'L', line),
+            number(id, 'R', line),
+            code(line)
+        )?;
+    }
+    Ok(())
+}
+
+fn fmt_split_hunk(f: &mut fmt::Formatter, id: &str, hunk: &Hunk) -> fmt::Result {
+    writeln!(
+        f,
+        "<tr class=\"hunk\"><td class=\"num\"></td><td class=\"code\" colspan=\"3\">{}</td></tr>",
+        hunk_header(hunk)
+    )?;
+    for (source, target) in hunk.paired_lines() {
+        write!(f, "<tr>")?;
+        for (side, line) in [('L', source), ('R', target)].iter() {
+            match *line {
+                Some(line) => write!(f, "{}{}", number(id, *side, line), code(line))?,
+                None => write!(
+                    f,
+                    "<td class=\"num empty\"></td><td class=\"code empty\"></td>"
+                )?,
+            }
+        }
+        writeln!(f, "</tr>")?;
+    }
+    Ok(())
+}
+
+impl PatchSet {
+    /// This patch set printed as an HTML document
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a<b\n+a>b\n".parse().unwrap();
+    /// let html = patch.html().split(true).to_string();
+    /// assert!(html.contains("<div class=\"file\" id=\"f1\">"));
+    /// assert!(html.contains("<a href=\"#f1-L1\">1</a></td><td class=\"code removed\">-a&lt;b</td>"));
+    /// ```
+    pub fn html(&self) -> Html<'_> {
+        Html::new(self)
+    }
+}
@@ -0,0 +1,179 @@
+//! Difference between two versions of a patch
+use std::collections::BTreeMap;
+
+use crate::change::{self, Change, CONTEXT};
+use crate::diff::diff;
+use crate::{Error, Hunk, LineType, PatchSet, PatchedFile, Result};
+
+/// A line of partially known file content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    /// Line whose content is known from a patch
+    Known(&'a str),
+    /// Known last line, without newline
+    NoNewline(&'a str),
+    /// Line of the base file no patch knows about, by line number
+    Unknown(usize),
+}
+
+/// Base file content known from the source side of both patches
+fn base<'a>(old: &'a [Hunk], new: &'a [Hunk]) -> Result<Vec<Token<'a>>> {
+    let mut known: BTreeMap<usize, &'a str> = BTreeMap::new();
+    for hunk in old.iter().chain(new.iter()) {
+        let mut source_line_no = hunk.source_first();
+        for line in hunk.lines() {
+            if !line.is_context() && !line.is_removed() {
+                continue;
+            }
+            if let Some(value) = known.insert(source_line_no, &line.value) {
+                if value != line.value {
+                    return Err(Error::Mismatch(format!(
+                        "line {} is both {:?} and {:?}",
+                        source_line_no, value, line.value
+                    )));
+                }
+            }
+            source_line_no += 1;
+        }
+    }
+    let no_newline = change::source_no_newline(old).or_else(|| change::source_no_newline(new));
+    let len = known.keys().next_back().cloned().unwrap_or(0);
+    Ok((1..=len)
+        .map(|n| match known.get(&n) {
+            Some(v) if no_newline == Some(n) => Token::NoNewline(v),
+            Some(v) => Token::Known(v),
+            None => Token::Unknown(n),
+        })
+        .collect())
+}
+
+/// Base lines from index `start` to `end`, unknown past the end of the known content
+fn span<'a, 'b>(
+    base: &'b [Token<'a>],
+    start: usize,
+    end: usize,
+) -> impl Iterator<Item = Token<'a>> + 'b {
+    (start..end).map(move |i| base.get(i).cloned().unwrap_or(Token::Unknown(i + 1)))
+}
+
+/// Apply hunks to partially known content
+fn apply<'a>(base: &[Token<'a>], hunks: &'a [Hunk]) -> Result<Vec<Token<'a>>> {
+    let mut result = vec![];
+    let mut cursor = 0;
+    for hunk in hunks {
+        // insertions without context may start past the known lines
+        let first = hunk.source_first().checked_sub(1).ok_or_else(|| {
+            Error::Mismatch(format!(
+                "hunk -{},{} starts before line 1",
+                hunk.source_start, hunk.source_length
+            ))
+        })?;
+        result.extend(span(base, cursor, first));
+        let lines = hunk.lines();
+        for (i, line) in lines.iter().enumerate() {
+            if !line.is_context() && !line.is_added() {
+                continue;
+            }
+            match lines.get(i + 1) {
+                Some(next) if next.line_type == LineType::NoNewlineMarker => {
+                    result.push(Token::NoNewline(&line.value))
+                }
+                _ => result.push(Token::Known(&line.value)),
+            }
+        }
+        cursor = first + hunk.source_length;
+    }
+    result.extend(span(base, cursor, base.len()));
+    Ok(result)
+}
+
+impl PatchedFile {
+    /// Difference between the results of this file and `other`, patched from the same base
+    ///
+    /// Returns `None` when both patches produce the same content.
+    pub fn interdiff(&self, other: &PatchedFile) -> Result<Option<PatchedFile>> {
+        let base = base(&self.hunks, &other.hunks)?;
+        let old = apply(&base, &self.hunks)?;
+        let new = apply(&base, &other.hunks)?;
+
+        let mut known = BTreeMap::new();
+        let mut no_newline = None;
+        for (n, token) in old.iter().enumerate() {
+            match *token {
+                Token::Known(value) => {
+                    known.insert(n + 1, value.to_owned());
+                }
+                Token::NoNewline(value) => {
+                    known.insert(n + 1, value.to_owned());
+                    no_newline = Some(n + 1);
+                }
+                Token::Unknown(_) => {}
+            }
+        }
+        let mut changes = vec![];
+        for (old_range, new_range) in diff(&old, &new) {
+            let mut change = Change::new(old_range.start + 1, vec![], vec![]);
+            for (lines, last_no_newline, tokens) in [
+                (
+                    &mut change.removed,
+                    &mut change.removed_no_newline,
+                    &old[old_range],
+                ),
+                (
+                    &mut change.added,
+                    &mut change.added_no_newline,
+                    &new[new_range],
+                ),
+            ] {
+                for token in tokens {
+                    match *token {
+                        Token::Known(value) => lines.push(value.to_owned()),
+                        Token::NoNewline(value) => {
+                            lines.push(value.to_owned());
+                            *last_no_newline = true;
+                        }
+                        Token::Unknown(n) => {
+                            return Err(Error::Mismatch(format!(
+                                "line {} of {} is not known to either patch",
+                                n,
+                                self.path()
+                            )))
+                        }
+                    }
+                }
+            }
+            changes.push(change);
+        }
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PatchedFile::with_hunks(
+            self.source_file.clone(),
+            other.target_file.clone(),
+            change::hunks(&changes, &known, no_newline, CONTEXT),
+        )))
+    }
+}
+
+impl PatchSet {
+    /// Difference between the results of this patch set and `other`, patched from the same base
+    ///
+    /// Like patchutils' `interdiff`, the result turns the content patched by this patch set into
+    /// the content patched by `other`. Files only changed by one of them are reverted or
+    /// copied as they are.
+    pub fn interdiff(&self, other: &PatchSet) -> Result<PatchSet> {
+        let mut patch = PatchSet::new();
+        for file in &self.files {
+            match other.files.iter().find(|f| f.path() == file.path()) {
+                Some(other_file) => patch.files.extend(file.interdiff(other_file)?),
+                None => patch.files.push(file.reverse()),
+            }
+        }
+        for other_file in &other.files {
+            if !self.files.iter().any(|f| f.path() == other_file.path()) {
+                patch.files.push(other_file.clone());
+            }
+        }
+        Ok(patch)
+    }
+}
@@ -22,30 +22,122 @@
 //!     patch.parse(diff_str).ok().expect("Error parsing diff");
 //! }
 //! ```
+//!
+//! # Serialization
+//!
+//! With the `serde` feature, `PatchSet`, `PatchedFile`, `Hunk`, `Line` and `ExtendedHeader`
+//! implement `Serialize` and `Deserialize`. A patch set is serialized as:
+//!
+//! ```json
+//! {
+//!   "files": [
+//!     {
+//!       "source_file": "a/file",
+//!       "source_timestamp": "",
+//!       "target_file": "b/file",
+//!       "target_timestamp": "",
+//!       "extended_headers": [{"similarity_index": 90}, {"index": "9b710f3..0000000 100644"}],
+//!       "binary": false,
+//!       "hunks": [
+//!         {
+//!           "added": 1,
+//!           "removed": 1,
+//!           "source_start": 1,
+//!           "source_length": 1,
+//!           "target_start": 1,
+//!           "target_length": 1,
+//!           "section_header": "",
+//!           "lines": [
+//!             {
+//!               "source_line_no": 1,
+//!               "target_line_no": null,
+//!               "diff_line_no": 6,
+//!               "line_type": "-",
+//!               "value": "old"
+//!             }
+//!           ]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Extended headers are objects with one snake case key, like `{"old_mode": "100644"}`.
+//! The `added` and `removed` counts of hunks are ignored when deserializing, they are
+//! counted again from the lines. The text kept by lossless parsing, the leniency and the
+//! encoding of a patch set are not serialized.
 use lazy_static::lazy_static;
 
+use std::convert::TryFrom;
 use std::error;
-use std::fmt;
+use std::fmt::{self, Write};
+use std::mem;
 use std::ops::{Index, IndexMut};
 use std::str::FromStr;
 
 use regex::Regex;
 
+use crate::lossless::{Header, Raw};
+
+mod borrowed;
+mod change;
+mod color;
+mod commute;
+mod context;
+mod diff;
+mod filter;
+mod git;
+mod grep;
+mod header;
+mod html;
+mod interdiff;
+mod lossless;
+mod paths;
+mod position;
+mod range_diff;
+mod reader;
+mod select;
+#[cfg(feature = "serde")]
+mod serialize;
+mod side_by_side;
+mod split;
+mod stat;
+mod visit;
+
+pub use crate::borrowed::{HunkRef, LineRef, PatchRef, PatchedFileRef};
+pub use crate::color::{Colored, Palette};
+pub use crate::commute::Conflict;
+pub use crate::filter::{Filter, MatchOn};
+pub use crate::grep::{Grep, GrepMatch};
+pub use crate::header::ExtendedHeader;
+pub use crate::html::Html;
+pub use crate::range_diff::{RangeDiff, RangeDiffEntry, DEFAULT_CREATION_FACTOR};
+pub use crate::reader::PatchReader;
+pub use crate::side_by_side::{SideBySide, DEFAULT_SIDE_BY_SIDE_WIDTH};
+pub use crate::stat::{Diffstat, Dirstat, FileStat, DEFAULT_DIRSTAT_LIMIT, DEFAULT_STAT_WIDTH};
+pub use crate::visit::{visit, visit_lenient, FileHeader, HunkHeader, Visitor};
+
 lazy_static! {
     static ref RE_DIFF_GIT_HEADER: Regex = Regex::new(r"^diff --git (?P<source_file>[^\s]+) (?P<target_file>[^\s]+)").unwrap();
     static ref RE_SOURCE_FILENAME: Regex = Regex::new(r"^--- (?P<filename>[^\t\n]+)(?:\t(?P<timestamp>[^\n]+))?").unwrap();
     static ref RE_TARGET_FILENAME: Regex = Regex::new(r"^\+\+\+ (?P<filename>[^\t\n]+)(?:\t(?P<timestamp>[^\n]+))?").unwrap();
     static ref RE_HUNK_HEADER: Regex = Regex::new(r"^@@ -(?P<source_start>\d+)(?:,(?P<source_length>\d+))? \+(?P<target_start>\d+)(?:,(?P<target_length>\d+))? @@[ ]?(?P<section_header>.*)").unwrap();
+    static ref RE_BINARY_FILES: Regex = Regex::new(r"^Binary files (?P<source_file>.+) and (?P<target_file>.+) differ$").unwrap();
     static ref RE_HUNK_BODY_LINE: Regex = Regex::new(r"^(?P<line_type>[- \n\+\\]?)(?P<value>.*)").unwrap();
 }
 
 /// Diff line is added
+#[deprecated(note = "use `LineType::Added`")]
 pub const LINE_TYPE_ADDED: &'static str = "+";
 /// Diff line is removed
+#[deprecated(note = "use `LineType::Removed`")]
 pub const LINE_TYPE_REMOVED: &'static str = "-";
 /// Diff line is context
+#[deprecated(note = "use `LineType::Context`")]
 pub const LINE_TYPE_CONTEXT: &'static str = " ";
 /// Diff line is empty
+#[deprecated(note = "empty lines are `LineType::Context`")]
 pub const LINE_TYPE_EMPTY: &'static str = "\n";
 
 /// Error type
@@ -57,6 +149,16 @@ pub enum Error {
     UnexpectedHunk(String),
     /// Hunk line expected
     ExpectLine(String),
+    /// Patch content does not match the content it is combined with
+    Mismatch(String),
+    /// Patches change the same lines
+    Conflict(Conflict),
+    /// File name cannot be rewritten
+    InvalidPath(String),
+    /// Input cannot be read
+    Io(String),
+    /// Line type cannot be parsed
+    InvalidLineType(String),
 }
 
 impl fmt::Display for Error {
@@ -65,6 +167,11 @@ impl fmt::Display for Error {
             Error::TargetWithoutSource(ref l) => write!(f, "Target without source: {}", l),
             Error::UnexpectedHunk(ref l) => write!(f, "Unexpected hunk found: {}", l),
             Error::ExpectLine(ref l) => write!(f, "Hunk line expected: {}", l),
+            Error::Mismatch(ref l) => write!(f, "Content mismatch: {}", l),
+            Error::Conflict(ref c) => write!(f, "Conflict: {}", c),
+            Error::InvalidPath(ref l) => write!(f, "Invalid path: {}", l),
+            Error::Io(ref l) => write!(f, "I/O error: {}", l),
+            Error::InvalidLineType(ref l) => write!(f, "Invalid line type: {:?}", l),
         }
     }
 }
@@ -75,6 +182,11 @@ impl error::Error for Error {
             Error::TargetWithoutSource(..) => "Target without source",
             Error::UnexpectedHunk(..) => "Unexpected hunk found",
             Error::ExpectLine(..) => "Hunk line expected",
+            Error::Mismatch(..) => "Content mismatch",
+            Error::Conflict(..) => "Conflict",
+            Error::InvalidPath(..) => "Invalid path",
+            Error::Io(..) => "I/O error",
+            Error::InvalidLineType(..) => "Invalid line type",
         }
     }
 }
@@ -82,8 +194,146 @@ impl error::Error for Error {
 /// `unidiff::parse` result type
 pub type Result<T> = ::std::result::Result<T, Error>;
 
+/// Type of a diff line, given by its first characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub enum LineType {
+    /// `+`, line added
+    Added,
+    /// `-`, line removed
+    Removed,
+    /// ` `, line unchanged
+    Context,
+    /// `\`, the line before has no newline
+    NoNewlineMarker,
+    /// Line of a combined diff, with a column of `+`, `-` or ` ` for each parent
+    ///
+    /// Combined diffs, with their `@@@` hunk headers, are not parsed: this line type is only
+    /// built by hand or converted from its columns, like `LineType::try_from("+ -")`.
+    Combined {
+        /// Count of parents, from 2 to 32
+        parents: u8,
+        /// Parents the line is added to, one bit for each
+        added: u32,
+        /// Parents the line is removed from, one bit for each
+        removed: u32,
+    },
+}
+
+impl LineType {
+    /// Column of a parent in a combined diff line
+    fn column(added: u32, removed: u32, parent: u8) -> char {
+        if added & 1 << parent != 0 {
+            '+'
+        } else if removed & 1 << parent != 0 {
+            '-'
+        } else {
+            ' '
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for LineType {
+    type Error = Error;
+
+    /// Line type of the first characters of a diff line, empty or a newline for empty context
+    /// lines
+    fn try_from(s: &'a str) -> Result<LineType> {
+        match s {
+            "+" => Ok(LineType::Added),
+            "-" => Ok(LineType::Removed),
+            " " | "" | "\n" => Ok(LineType::Context),
+            "\\" => Ok(LineType::NoNewlineMarker),
+            _ if s.len() > 1 && s.len() <= 32 && s.chars().all(|c| " +-".contains(c)) => {
+                let (mut added, mut removed) = (0, 0);
+                for (i, c) in s.chars().enumerate() {
+                    match c {
+                        '+' => added |= 1 << i,
+                        '-' => removed |= 1 << i,
+                        _ => {}
+                    }
+                }
+                Ok(LineType::Combined {
+                    parents: s.len() as u8,
+                    added,
+                    removed,
+                })
+            }
+            _ => Err(Error::InvalidLineType(s.to_owned())),
+        }
+    }
+}
+
+impl TryFrom<String> for LineType {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<LineType> {
+        LineType::try_from(s.as_str())
+    }
+}
+
+impl From<LineType> for String {
+    fn from(line_type: LineType) -> String {
+        line_type.to_string()
+    }
+}
+
+impl PartialEq<str> for LineType {
+    fn eq(&self, other: &str) -> bool {
+        match *self {
+            LineType::Added => other == "+",
+            LineType::Removed => other == "-",
+            LineType::Context => other == " ",
+            LineType::NoNewlineMarker => other == "\\",
+            LineType::Combined {
+                parents,
+                added,
+                removed,
+            } => {
+                other.len() == parents as usize
+                    && other
+                        .chars()
+                        .zip(0..parents)
+                        .all(|(c, i)| c == LineType::column(added, removed, i))
+            }
+        }
+    }
+}
+
+impl<'a> PartialEq<&'a str> for LineType {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl fmt::Display for LineType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LineType::Added => f.write_str("+"),
+            LineType::Removed => f.write_str("-"),
+            LineType::Context => f.write_str(" "),
+            LineType::NoNewlineMarker => f.write_str("\\"),
+            LineType::Combined {
+                parents,
+                added,
+                removed,
+            } => {
+                for i in 0..parents {
+                    f.write_char(LineType::column(added, removed, i))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// A diff line
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
     /// Source file line number
     pub source_line_no: Option<usize>,
@@ -92,35 +342,43 @@ pub struct Line {
     /// Diff file line number
     pub diff_line_no: usize,
     /// Diff line type
-    pub line_type: String,
+    pub line_type: LineType,
     /// Diff line content value
     pub value: String,
 }
 
 impl Line {
-    pub fn new<T: Into<String>>(value: T, line_type: T) -> Line {
+    pub fn new<T: Into<String>>(value: T, line_type: LineType) -> Line {
         Line {
             source_line_no: Some(0usize),
             target_line_no: Some(0usize),
             diff_line_no: 0usize,
-            line_type: line_type.into(),
+            line_type,
             value: value.into(),
         }
     }
 
+    /// Line with a line type given by its first characters, like `"+"`
+    ///
+    /// Fails when `line_type` is not a valid line type.
+    pub fn try_new<T: Into<String>, L: AsRef<str>>(value: T, line_type: L) -> Result<Line> {
+        let line_type = LineType::try_from(line_type.as_ref())?;
+        Ok(Line::new(value, line_type))
+    }
+
     /// Diff line type is added
     pub fn is_added(&self) -> bool {
-        LINE_TYPE_ADDED == &self.line_type
+        self.line_type == LineType::Added
     }
 
     /// Diff line type is removed
     pub fn is_removed(&self) -> bool {
-        LINE_TYPE_REMOVED == &self.line_type
+        self.line_type == LineType::Removed
     }
 
     /// Diff line type is context
     pub fn is_context(&self) -> bool {
-        LINE_TYPE_CONTEXT == &self.line_type
+        self.line_type == LineType::Context
     }
 }
 
@@ -134,6 +392,11 @@ impl fmt::Display for Line {
 ///
 /// You can iterate over it to get ``Line``s.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "crate::serialize::HunkData")
+)]
 pub struct Hunk {
     /// Count of lines added
     added: usize,
@@ -150,8 +413,8 @@ pub struct Hunk {
     /// Section header
     pub section_header: String,
     lines: Vec<Line>,
-    source: Vec<String>,
-    target: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    raw: Raw<String>,
 }
 
 impl Hunk {
@@ -171,8 +434,7 @@ impl Hunk {
             target_length: target_length,
             section_header: section_header.into(),
             lines: vec![],
-            source: vec![],
-            target: vec![],
+            raw: Raw::default(),
         }
     }
 
@@ -188,7 +450,12 @@ impl Hunk {
 
     /// Is this hunk valid
     pub fn is_valid(&self) -> bool {
-        self.source.len() == self.source_length && self.target.len() == self.target_length
+        let source = self
+            .lines
+            .iter()
+            .filter(|l| l.is_context() || l.is_removed());
+        let target = self.lines.iter().filter(|l| l.is_context() || l.is_added());
+        source.count() == self.source_length && target.count() == self.target_length
     }
 
     /// Lines from source file
@@ -213,17 +480,8 @@ impl Hunk {
     pub fn append(&mut self, line: Line) {
         if line.is_added() {
             self.added = self.added + 1;
-            self.target
-                .push(format!("{}{}", line.line_type, line.value));
         } else if line.is_removed() {
             self.removed = self.removed + 1;
-            self.source
-                .push(format!("{}{}", line.line_type, line.value));
-        } else if line.is_context() {
-            self.source
-                .push(format!("{}{}", line.line_type, line.value));
-            self.target
-                .push(format!("{}{}", line.line_type, line.value));
         }
         self.lines.push(line);
     }
@@ -246,6 +504,119 @@ impl Hunk {
     pub fn lines_mut(&mut self) -> &mut [Line] {
         &mut self.lines
     }
+
+    /// Rebuild lengths and line numbers from the lines of this hunk
+    pub fn recount(&mut self) {
+        let source_first = self.source_first();
+        let target_first = self.target_first();
+        self.renumber(source_first, target_first);
+    }
+
+    /// Reversed hunk, undoing the changes of this one
+    pub fn reverse(&self) -> Hunk {
+        let mut hunk = Hunk::new(
+            self.target_start,
+            self.target_length,
+            self.source_start,
+            self.source_length,
+            self.section_header.clone(),
+        );
+        // removed lines are kept in front of added ones within each change
+        let mut added = vec![];
+        let mut last_added = false;
+        for line in &self.lines {
+            let mut line = line.clone();
+            mem::swap(&mut line.source_line_no, &mut line.target_line_no);
+            if line.is_added() {
+                line.line_type = LineType::Removed;
+                hunk.append(line);
+                last_added = false;
+            } else if line.is_removed() {
+                line.line_type = LineType::Added;
+                added.push(line);
+                last_added = true;
+            } else if line.is_context() {
+                for line in added.drain(..) {
+                    hunk.append(line);
+                }
+                hunk.append(line);
+                last_added = false;
+            } else if last_added {
+                added.push(line);
+            } else {
+                hunk.append(line);
+            }
+        }
+        for line in added {
+            hunk.append(line);
+        }
+        hunk
+    }
+
+    /// Build a hunk from lines, numbering them from the first source and target line numbers
+    pub(crate) fn from_lines<T: Into<String>>(
+        source_first: usize,
+        target_first: usize,
+        section_header: T,
+        lines: Vec<Line>,
+    ) -> Hunk {
+        let mut hunk = Hunk::new(0, 0, 0, 0, section_header);
+        hunk.lines = lines;
+        hunk.renumber(source_first, target_first);
+        hunk
+    }
+
+    /// Line number of the first source line covered by this hunk
+    pub(crate) fn source_first(&self) -> usize {
+        if self.source_length == 0 {
+            self.source_start + 1
+        } else {
+            self.source_start
+        }
+    }
+
+    /// Line number of the first target line covered by this hunk
+    pub(crate) fn target_first(&self) -> usize {
+        if self.target_length == 0 {
+            self.target_start + 1
+        } else {
+            self.target_start
+        }
+    }
+
+    /// Renumber lines from the first source and target line numbers and update the header
+    pub(crate) fn renumber(&mut self, source_first: usize, target_first: usize) {
+        let lines = mem::take(&mut self.lines);
+        self.added = 0;
+        self.removed = 0;
+        let mut source_line_no = source_first;
+        let mut target_line_no = target_first;
+        for mut line in lines {
+            line.source_line_no = None;
+            line.target_line_no = None;
+            if line.is_added() || line.is_context() {
+                line.target_line_no = Some(target_line_no);
+                target_line_no += 1;
+            }
+            if line.is_removed() || line.is_context() {
+                line.source_line_no = Some(source_line_no);
+                source_line_no += 1;
+            }
+            self.append(line);
+        }
+        self.source_length = source_line_no - source_first;
+        self.target_length = target_line_no - target_first;
+        self.source_start = if self.source_length == 0 {
+            source_first.saturating_sub(1)
+        } else {
+            source_first
+        };
+        self.target_start = if self.target_length == 0 {
+            target_first.saturating_sub(1)
+        } else {
+            target_first
+        };
+    }
 }
 
 impl fmt::Display for Hunk {
@@ -295,6 +666,7 @@ impl IndexMut<usize> for Hunk {
 ///
 /// You can iterate over it to get ``Hunk``s.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PatchedFile {
     /// Source file name
     pub source_file: String,
@@ -304,7 +676,11 @@ pub struct PatchedFile {
     pub target_file: String,
     /// Target file timestamp
     pub target_timestamp: Option<String>,
+    extended_headers: Vec<ExtendedHeader>,
+    binary: bool,
     hunks: Vec<Hunk>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    raw_header: Raw<Header>,
 }
 
 impl PatchedFile {
@@ -315,7 +691,10 @@ impl PatchedFile {
             target_file: target_file.into(),
             source_timestamp: None,
             target_timestamp: None,
+            extended_headers: vec![],
+            binary: false,
             hunks: vec![],
+            raw_header: Raw::default(),
         }
     }
 
@@ -330,7 +709,10 @@ impl PatchedFile {
             target_file: target_file.into(),
             source_timestamp: None,
             target_timestamp: None,
+            extended_headers: vec![],
+            binary: false,
             hunks: hunks,
+            raw_header: Raw::default(),
         }
     }
 
@@ -377,6 +759,41 @@ impl PatchedFile {
             && (!self.hunks.is_empty() || !self.is_renamed_file())
     }
 
+    /// Reversed file, undoing the changes of this one
+    ///
+    /// Copy headers are dropped, as a copy has no reverse.
+    pub fn reverse(&self) -> PatchedFile {
+        // git `a/` and `b/` prefixes stay on their side
+        let (source_prefix, target_prefix) = paths::prefixes(self);
+        let (source, target) = paths::names(self);
+        let prefixed = |prefix: &str, name: &str| {
+            if name == "/dev/null" {
+                name.to_owned()
+            } else {
+                format!("{}{}", prefix, name)
+            }
+        };
+        PatchedFile {
+            source_file: prefixed(source_prefix, target),
+            source_timestamp: self.target_timestamp.clone(),
+            target_file: prefixed(target_prefix, source),
+            target_timestamp: self.source_timestamp.clone(),
+            extended_headers: self
+                .extended_headers
+                .iter()
+                .filter_map(|h| h.reverse())
+                .collect(),
+            binary: self.binary,
+            hunks: self.hunks.iter().map(|h| h.reverse()).collect(),
+            raw_header: Raw::default(),
+        }
+    }
+
+    /// Is this file binary, changed without hunks
+    pub fn is_binary_file(&self) -> bool {
+        self.binary
+    }
+
     /// Is this file renamed
     pub fn is_renamed_file(&self) -> bool {
         self.source_file.trim_start_matches("a/") != self.target_file.trim_start_matches("b/")
@@ -384,89 +801,14 @@ impl PatchedFile {
             && self.target_file != "/dev/null"
     }
 
-    fn parse_hunk(&mut self, header: &str, diff: &[(usize, &str)]) -> Result<()> {
-        let header_info = RE_HUNK_HEADER.captures(header).unwrap();
-        let source_start = header_info
-            .name("source_start")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let source_length = header_info
-            .name("source_length")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let target_start = header_info
-            .name("target_start")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let target_length = header_info
-            .name("target_length")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let section_header = header_info
-            .name("section_header")
-            .map_or("", |s| s.as_str());
-        let mut hunk = Hunk {
-            added: 0usize,
-            removed: 0usize,
-            source: vec![],
-            target: vec![],
-            lines: vec![],
-            source_start: source_start,
-            source_length: source_length,
-            target_start: target_start,
-            target_length: target_length,
-            section_header: section_header.to_owned(),
-        };
-        let mut source_line_no = source_start;
-        let mut target_line_no = target_start;
-        let expected_source_end = source_start + source_length;
-        let expected_target_end = target_start + target_length;
-        for &(diff_line_no, line) in diff {
-            if let Some(valid_line) = RE_HUNK_BODY_LINE.captures(line) {
-                let mut line_type = valid_line.name("line_type").unwrap().as_str();
-                if line_type == LINE_TYPE_EMPTY || line_type == "" {
-                    line_type = LINE_TYPE_CONTEXT;
-                }
-                let value = valid_line.name("value").unwrap().as_str();
-                let mut original_line = Line {
-                    source_line_no: None,
-                    target_line_no: None,
-                    diff_line_no: diff_line_no + 1,
-                    line_type: line_type.to_owned(),
-                    value: value.to_owned(),
-                };
-                match line_type {
-                    LINE_TYPE_ADDED => {
-                        original_line.target_line_no = Some(target_line_no);
-                        target_line_no = target_line_no + 1;
-                    }
-                    LINE_TYPE_REMOVED => {
-                        original_line.source_line_no = Some(source_line_no);
-                        source_line_no = source_line_no + 1;
-                    }
-                    LINE_TYPE_CONTEXT => {
-                        original_line.target_line_no = Some(target_line_no);
-                        target_line_no = target_line_no + 1;
-                        original_line.source_line_no = Some(source_line_no);
-                        source_line_no = source_line_no + 1;
-                    }
-                    _ => {}
-                }
-                hunk.append(original_line);
-                if source_line_no >= expected_source_end && target_line_no >= expected_target_end {
-                    // FIXME: sync with upstream version
-                    break;
-                }
-            } else {
-                return Err(Error::ExpectLine(line.to_owned()));
-            }
+    /// Rebuild hunk lengths from their lines, and starts from the changes of previous hunks
+    pub fn recount(&mut self) {
+        let mut delta = 0isize;
+        for hunk in &mut self.hunks {
+            let source_first = hunk.source_first();
+            hunk.renumber(source_first, (source_first as isize + delta) as usize);
+            delta += hunk.added as isize - hunk.removed as isize;
         }
-        self.hunks.push(hunk);
-        Ok(())
     }
 
     /// Count of hunks
@@ -486,6 +828,15 @@ impl PatchedFile {
     pub fn hunks_mut(&mut self) -> &mut [Hunk] {
         &mut self.hunks
     }
+
+    /// Git extended headers of this file
+    pub fn extended_headers(&self) -> &[ExtendedHeader] {
+        &self.extended_headers
+    }
+
+    pub fn extended_headers_mut(&mut self) -> &mut Vec<ExtendedHeader> {
+        &mut self.extended_headers
+    }
 }
 
 impl fmt::Display for PatchedFile {
@@ -543,9 +894,20 @@ impl IndexMut<usize> for PatchedFile {
 /// }
 /// ```
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PatchSet {
     files: Vec<PatchedFile>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    lenient: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    lossless: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    trailer: String,
     #[cfg(feature = "encoding")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip, default = "crate::serialize::default_encoding")
+    )]
     encoding: &'static encoding_rs::Encoding,
 }
 
@@ -591,12 +953,22 @@ impl PatchSet {
             .collect()
     }
 
+    /// Reversed patch set, undoing the changes of this one
+    pub fn reverse(&self) -> PatchSet {
+        let mut patch = self.clone();
+        patch.files = self.files.iter().map(|f| f.reverse()).collect();
+        patch
+    }
+
     /// Initialize a new PatchSet instance
     pub fn new() -> PatchSet {
         PatchSet {
             files: vec![],
             #[cfg(feature = "encoding")]
             encoding: encoding_rs::UTF_8,
+            lenient: false,
+            lossless: false,
+            trailer: String::new(),
         }
     }
 
@@ -606,6 +978,9 @@ impl PatchSet {
         PatchSet {
             files: vec![],
             encoding: coding,
+            lenient: false,
+            lossless: false,
+            trailer: String::new(),
         }
     }
 
@@ -616,6 +991,48 @@ impl PatchSet {
         PatchSet {
             files: vec![],
             encoding: codec.unwrap_or(encoding_rs::UTF_8),
+            lenient: false,
+            lossless: false,
+            trailer: String::new(),
+        }
+    }
+
+    /// Set whether to ignore hunk header lengths when parsing
+    ///
+    /// In lenient mode, hunks extend over every following line that looks like a hunk line,
+    /// and their lengths are recounted, like `git apply --recount`. This accepts hand edited
+    /// patches with wrong header counts.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Are hunk header lengths ignored when parsing
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Set whether to keep the text of file headers and hunks when parsing
+    ///
+    /// The kept text is written back by `to_lossless_string`, at the cost of a copy of the
+    /// whole diff.
+    pub fn set_lossless(&mut self, lossless: bool) {
+        self.lossless = lossless;
+    }
+
+    /// Is the text of file headers and hunks kept when parsing
+    pub fn is_lossless(&self) -> bool {
+        self.lossless
+    }
+
+    /// Text found after the last file, or all the text parsed when no file was found
+    pub fn trailer(&self) -> &str {
+        &self.trailer
+    }
+
+    /// Rebuild hunk lengths and starts from the lines of every file
+    pub fn recount(&mut self) {
+        for file in &mut self.files {
+            file.recount();
         }
     }
 
@@ -628,95 +1045,7 @@ impl PatchSet {
 
     /// Parse diff from string
     pub fn parse<T: AsRef<str>>(&mut self, input: T) -> Result<()> {
-        let input = input.as_ref();
-        let mut current_file: Option<PatchedFile> = None;
-        let diff: Vec<(usize, &str)> = input.lines().enumerate().collect();
-
-        let mut git_header_found = false;
-        let mut source_file: Option<String> = None;
-        let mut source_timestamp: Option<String> = None;
-
-        macro_rules! flush {
-            () => {
-                if let Some(patched_file) = current_file.take() {
-                    self.files.push(patched_file);
-                    git_header_found = false;
-                    source_file = None;
-                    source_timestamp = None;
-                    current_file = None;
-                }
-            };
-        }
-
-        for &(line_no, line) in &diff {
-            if let Some(captures) = RE_DIFF_GIT_HEADER.captures(line) {
-                flush!();
-
-                // add current file to PatchSet
-                current_file = Some(PatchedFile {
-                    source_file: captures.name("source_file").unwrap().as_str().to_owned(),
-                    target_file: captures.name("target_file").unwrap().as_str().to_owned(),
-                    source_timestamp: None,
-                    target_timestamp: None,
-                    hunks: Vec::new(),
-                });
-                git_header_found = true;
-
-                continue;
-            }
-
-            // check for source file header
-            if let Some(captures) = RE_SOURCE_FILENAME.captures(line) {
-                if !git_header_found {
-                    flush!();
-                }
-
-                source_file = match captures.name("filename") {
-                    Some(ref filename) => Some(filename.as_str().to_owned()),
-                    None => Some("".to_owned()),
-                };
-                source_timestamp = match captures.name("timestamp") {
-                    Some(ref timestamp) => Some(timestamp.as_str().to_owned()),
-                    None => Some("".to_owned()),
-                };
-
-                continue;
-            }
-            // check for target file header
-            if let Some(captures) = RE_TARGET_FILENAME.captures(line) {
-                if !git_header_found && current_file.is_some() {
-                    return Err(Error::TargetWithoutSource(line.to_owned()));
-                }
-                let target_file = match captures.name("filename") {
-                    Some(ref filename) => Some(filename.as_str().to_owned()),
-                    None => Some("".to_owned()),
-                };
-                let target_timestamp = match captures.name("timestamp") {
-                    Some(ref timestamp) => Some(timestamp.as_str().to_owned()),
-                    None => Some("".to_owned()),
-                };
-
-                // add current file to PatchSet
-                current_file = Some(PatchedFile {
-                    source_file: source_file.clone().unwrap(),
-                    target_file: target_file.clone().unwrap(),
-                    source_timestamp: source_timestamp.clone(),
-                    target_timestamp: target_timestamp.clone(),
-                    hunks: Vec::new(),
-                });
-                continue;
-            }
-            // check for hunk header
-            if RE_HUNK_HEADER.is_match(line) {
-                if let Some(ref mut patched_file) = current_file {
-                    patched_file.parse_hunk(line, &diff[line_no + 1..])?;
-                } else {
-                    return Err(Error::UnexpectedHunk(line.to_owned()));
-                }
-            }
-        }
-        flush!();
-        Ok(())
+        self.parse_str(input.as_ref(), 0)
     }
 
     /// Count of patched files
@@ -0,0 +1,180 @@
+//! Writing parsed diffs back as they were parsed
+use std::hash::{Hash, Hasher};
+
+use crate::visit::{visit_with, FileHeader, Visitor};
+use crate::{ExtendedHeader, Hunk, PatchSet, PatchedFile, RE_HUNK_HEADER};
+
+/// Text a value was parsed from, with a snapshot of the value telling whether it changed
+/// since, ignored when comparing values
+#[derive(Debug, Clone)]
+pub(crate) struct Raw<T>(pub(crate) Option<(String, T)>);
+
+impl<T> Default for Raw<T> {
+    fn default() -> Raw<T> {
+        Raw(None)
+    }
+}
+
+impl<T> PartialEq for Raw<T> {
+    fn eq(&self, _other: &Raw<T>) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for Raw<T> {}
+
+impl<T> Hash for Raw<T> {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+/// Fields of a file header, as they were when the header was parsed
+#[derive(Debug, Clone)]
+pub(crate) struct Header {
+    source_file: String,
+    source_timestamp: Option<String>,
+    target_file: String,
+    target_timestamp: Option<String>,
+    extended_headers: Vec<ExtendedHeader>,
+    binary: bool,
+}
+
+impl Header {
+    /// Snapshot of the header of `file`
+    pub(crate) fn of(file: &PatchedFile) -> Header {
+        Header {
+            source_file: file.source_file.clone(),
+            source_timestamp: file.source_timestamp.clone(),
+            target_file: file.target_file.clone(),
+            target_timestamp: file.target_timestamp.clone(),
+            extended_headers: file.extended_headers.clone(),
+            binary: file.binary,
+        }
+    }
+
+    /// Is this the header of `file`
+    fn matches(&self, file: &PatchedFile) -> bool {
+        self.source_file == file.source_file
+            && self.source_timestamp == file.source_timestamp
+            && self.target_file == file.target_file
+            && self.target_timestamp == file.target_timestamp
+            && self.extended_headers == file.extended_headers
+            && self.binary == file.binary
+    }
+}
+
+/// First file header of a text: its line index and whether it is a git or binary header
+#[derive(Default)]
+struct FirstHeader(Option<(usize, bool)>);
+
+impl<'a> Visitor<'a> for FirstHeader {
+    fn on_file_header(&mut self, header: FileHeader<'a>, diff_line_no: usize) {
+        if self.0.is_none() {
+            // unified headers are seen on their `+++` line
+            self.0 = Some(match header {
+                FileHeader::Unified { .. } => (diff_line_no - 2, false),
+                _ => (diff_line_no - 1, true),
+            });
+        }
+    }
+}
+
+impl Hunk {
+    /// This hunk as it was parsed, or as displayed when it changed since, ending with a newline
+    ///
+    /// Text found between the previous hunk and this one is kept with it.
+    pub fn to_lossless_string(&self) -> String {
+        if let Some((ref raw, ref parsed)) = self.raw.0 {
+            // unchanged since parsed when displayed the same
+            let display = self.to_string();
+            if display == *parsed {
+                return raw.clone();
+            }
+            // keep the text before the header
+            let header = raw
+                .split_inclusive('\n')
+                .position(|l| RE_HUNK_HEADER.is_match(l))
+                .unwrap_or(0);
+            let junk: String = raw.split_inclusive('\n').take(header).collect();
+            return format!("{}{}\n", junk, display);
+        }
+        format!("{}\n", self)
+    }
+}
+
+impl PatchedFile {
+    /// Header of this file as it was parsed, or as displayed when it changed since
+    ///
+    /// Changed headers keep the text before them, and are written like `git diff` does
+    /// when they were git headers or this file has extended headers.
+    fn lossless_header(&self) -> String {
+        let mut junk = String::new();
+        let mut git = !self.extended_headers.is_empty();
+        if let Some((ref raw, ref parsed)) = self.raw_header.0 {
+            if parsed.matches(self) {
+                return raw.clone();
+            }
+            let mut first = FirstHeader::default();
+            if visit_with(raw, 0, true, &mut first).is_ok() {
+                if let Some((start, git_header)) = first.0 {
+                    junk = raw.split_inclusive('\n').take(start).collect();
+                    git |= git_header;
+                }
+            }
+        }
+        if git || self.binary {
+            let (header, binary) = self.git_header();
+            for line in header.iter().chain(binary.iter()) {
+                junk.push_str(line);
+                junk.push('\n');
+            }
+            return junk;
+        }
+        let timestamp = |t: &Option<String>| match *t {
+            Some(ref t) if !t.is_empty() => format!("\t{}", t),
+            _ => "".to_owned(),
+        };
+        format!(
+            "{}--- {}{}\n+++ {}{}\n",
+            junk,
+            self.source_file,
+            timestamp(&self.source_timestamp),
+            self.target_file,
+            timestamp(&self.target_timestamp)
+        )
+    }
+
+    /// This file as it was parsed, with the parts changed since as displayed
+    ///
+    /// Text found before the header of this file is kept with it.
+    pub fn to_lossless_string(&self) -> String {
+        let mut text = self.lossless_header();
+        for hunk in &self.hunks {
+            text.push_str(&hunk.to_lossless_string());
+        }
+        text
+    }
+}
+
+impl PatchSet {
+    /// This patch set as it was parsed, with the parts changed since as displayed
+    ///
+    /// Parsing with `set_lossless` and writing back an unchanged patch set gives the same
+    /// text, including line endings and text that is not part of a diff. Only hunks and file
+    /// headers changed after parsing, or parsed without keeping their text, are written like
+    /// `Display` does.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let diff = "From: someone\n--- a/a.txt\t2020-01-01\n+++ b/a.txt\n@@ -1 +1 @@\n-a\n+b\n";
+    /// let mut patch = PatchSet::new();
+    /// patch.set_lossless(true);
+    /// patch.parse(diff).unwrap();
+    /// assert_eq!(diff, patch.to_lossless_string());
+    /// ```
+    pub fn to_lossless_string(&self) -> String {
+        let mut text: String = self.files.iter().map(|f| f.to_lossless_string()).collect();
+        text.push_str(&self.trailer);
+        text
+    }
+}
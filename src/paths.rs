@@ -0,0 +1,188 @@
+//! Rewriting file names of a patch set
+use crate::{Error, ExtendedHeader, PatchSet, PatchedFile, Result};
+
+/// Path without its `n` leading components, like `patch -p`
+///
+/// Returns `None` when the path has fewer components.
+pub(crate) fn strip_components(path: &str, n: usize) -> Option<&str> {
+    let mut rest = path;
+    for _ in 0..n {
+        let i = rest.find('/')?;
+        rest = rest[i + 1..].trim_start_matches('/');
+    }
+    Some(rest)
+}
+
+/// Git `a/` and `b/` prefixes of the source and target file names, if they have them
+pub(crate) fn prefixes(file: &PatchedFile) -> (&'static str, &'static str) {
+    let is_git = |name: &str, prefix: &str| name == "/dev/null" || name.starts_with(prefix);
+    if is_git(&file.source_file, "a/") && is_git(&file.target_file, "b/") {
+        ("a/", "b/")
+    } else {
+        ("", "")
+    }
+}
+
+/// Source and target file names, without the git `a/` and `b/` prefixes
+pub(crate) fn names(file: &PatchedFile) -> (&str, &str) {
+    let (source, target) = prefixes(file);
+    (
+        file.source_file
+            .strip_prefix(source)
+            .unwrap_or(&file.source_file),
+        file.target_file
+            .strip_prefix(target)
+            .unwrap_or(&file.target_file),
+    )
+}
+
+impl PatchedFile {
+    /// This file with `n` leading components stripped from its file names, like `patch -p`
+    ///
+    /// The paths of rename and copy headers are stripped along. `/dev/null` is left as it
+    /// is. Fails when a file name has fewer components.
+    pub fn strip_prefix(&self, n: usize) -> Result<PatchedFile> {
+        self.rename(|name, _| {
+            if name == "/dev/null" {
+                return Ok(name.to_owned());
+            }
+            strip_components(name, n)
+                .map(|s| s.to_owned())
+                .ok_or_else(|| {
+                    Error::InvalidPath(format!("{} has fewer than {} leading components", name, n))
+                })
+        })
+    }
+
+    /// This file with prefixes added to its source and target file names
+    ///
+    /// The paths of rename and copy headers get the prefixes too. `/dev/null` is left as it
+    /// is.
+    pub fn add_prefix(&self, source: &str, target: &str) -> PatchedFile {
+        let renamed = self.rename(|name, is_target| {
+            if name == "/dev/null" {
+                return Ok(name.to_owned());
+            }
+            let prefix = if is_target { target } else { source };
+            Ok(format!("{}{}", prefix, name))
+        });
+        renamed.unwrap()
+    }
+
+    /// This file with its file names rewritten by `rename`, given whether they are targets
+    ///
+    /// Rename and copy header paths are rewritten as file names with the git prefix of the
+    /// original name, which is removed again if the rewritten file names still have it.
+    fn rename<F: FnMut(&str, bool) -> Result<String>>(&self, mut rename: F) -> Result<PatchedFile> {
+        let old_prefixes = prefixes(self);
+        let mut file = self.clone();
+        file.source_file = rename(&self.source_file, false)?;
+        file.target_file = rename(&self.target_file, true)?;
+        let new_prefixes = prefixes(&file);
+        for header in &mut file.extended_headers {
+            let (path, is_target) = match *header {
+                ExtendedHeader::CopyFrom(ref mut path)
+                | ExtendedHeader::RenameFrom(ref mut path) => (path, false),
+                ExtendedHeader::CopyTo(ref mut path) | ExtendedHeader::RenameTo(ref mut path) => {
+                    (path, true)
+                }
+                _ => continue,
+            };
+            let (old_prefix, new_prefix) = if is_target {
+                (old_prefixes.1, new_prefixes.1)
+            } else {
+                (old_prefixes.0, new_prefixes.0)
+            };
+            let renamed = rename(&format!("{}{}", old_prefix, path), is_target)?;
+            *path = renamed
+                .strip_prefix(new_prefix)
+                .unwrap_or(&renamed)
+                .to_owned();
+        }
+        Ok(file)
+    }
+
+    /// This file with every path rewritten by `map`
+    ///
+    /// Source and target file names and the paths of rename and copy headers are rewritten.
+    /// As for `path`, the `a/` and `b/` prefixes of git file names are not given to `map`
+    /// and are kept. `/dev/null` is left as it is.
+    pub fn map_paths<F: FnMut(&str) -> String>(&self, mut map: F) -> PatchedFile {
+        let prefixes = prefixes(self);
+        let mut map_name = |name: &str, prefix: &str| {
+            if name == "/dev/null" {
+                name.to_owned()
+            } else {
+                format!("{}{}", prefix, map(&name[prefix.len()..]))
+            }
+        };
+        let mut file = self.clone();
+        file.source_file = map_name(&self.source_file, prefixes.0);
+        file.target_file = map_name(&self.target_file, prefixes.1);
+        for header in &mut file.extended_headers {
+            match *header {
+                ExtendedHeader::CopyFrom(ref mut path)
+                | ExtendedHeader::CopyTo(ref mut path)
+                | ExtendedHeader::RenameFrom(ref mut path)
+                | ExtendedHeader::RenameTo(ref mut path) => *path = map_name(path, ""),
+                _ => {}
+            }
+        }
+        file
+    }
+}
+
+impl PatchSet {
+    /// This patch set with `n` leading components stripped from file names, like `patch -p`
+    ///
+    /// `/dev/null` is left as it is. Fails when a file name has fewer components.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let patch = patch.strip_prefix(1).unwrap().add_prefix("a/crate/", "b/crate/");
+    /// assert_eq!("a/crate/src/lib.rs", patch[0].source_file);
+    /// ```
+    pub fn strip_prefix(&self, n: usize) -> Result<PatchSet> {
+        let mut patch = self.clone();
+        patch.files = self
+            .files
+            .iter()
+            .map(|f| f.strip_prefix(n))
+            .collect::<Result<Vec<PatchedFile>>>()?;
+        Ok(patch)
+    }
+
+    /// This patch set with prefixes added to source and target file names
+    pub fn add_prefix(&self, source: &str, target: &str) -> PatchSet {
+        let mut patch = self.clone();
+        patch.files = self
+            .files
+            .iter()
+            .map(|f| f.add_prefix(source, target))
+            .collect();
+        patch
+    }
+
+    /// This patch set with every path rewritten by `map`, see `PatchedFile::map_paths`
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/old.rs\n+++ b/old.rs\n@@ -1 +1 @@\n-a\n+b\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let table: HashMap<&str, &str> = vec![("old.rs", "src/new.rs")].into_iter().collect();
+    /// let patch = patch.map_paths(|p| table.get(p).unwrap_or(&p).to_string());
+    /// assert_eq!("b/src/new.rs", patch[0].target_file);
+    /// ```
+    pub fn map_paths<F: FnMut(&str) -> String>(&self, mut map: F) -> PatchSet {
+        let mut patch = self.clone();
+        patch.files = self.files.iter().map(|f| f.map_paths(&mut map)).collect();
+        patch
+    }
+}
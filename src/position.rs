@@ -0,0 +1,75 @@
+//! Mapping line numbers between the source and target of a patched file, and to positions
+//! within its diff
+use crate::change;
+use crate::{Line, PatchedFile};
+
+impl PatchedFile {
+    /// Target line number of a source line, `None` if this file removes it
+    ///
+    /// Any line of the source file can be mapped, not only lines inside hunks.
+    pub fn source_to_target(&self, source_line_no: usize) -> Option<usize> {
+        change::to_target(&change::changes(&self.hunks), source_line_no)
+    }
+
+    /// Source line number of a target line, `None` if this file adds it
+    ///
+    /// Any line of the target file can be mapped, not only lines inside hunks.
+    pub fn target_to_source(&self, target_line_no: usize) -> Option<usize> {
+        change::to_source(&change::changes(&self.hunks), target_line_no)
+    }
+
+    /// Lines of this file with their position in its diff
+    ///
+    /// As for the GitHub review API, the line following the first hunk header is at
+    /// position 1, and the headers of the following hunks take a position too.
+    fn positions(&self) -> Vec<(usize, &Line)> {
+        let mut positions = vec![];
+        let mut position = 0;
+        for hunk in &self.hunks {
+            if position > 0 {
+                position += 1;
+            }
+            for line in hunk.lines() {
+                position += 1;
+                positions.push((position, line));
+            }
+        }
+        positions
+    }
+
+    /// Line at a diff position, `None` for a hunk header or past the end
+    pub fn line_at_position(&self, position: usize) -> Option<&Line> {
+        self.positions()
+            .into_iter()
+            .find(|&(p, _)| p == position)
+            .map(|(_, line)| line)
+    }
+
+    /// Diff position of a removed or context line, by source line number
+    pub fn source_line_position(&self, source_line_no: usize) -> Option<usize> {
+        self.positions()
+            .into_iter()
+            .find(|&(_, l)| {
+                (l.is_removed() || l.is_context()) && l.source_line_no == Some(source_line_no)
+            })
+            .map(|(p, _)| p)
+    }
+
+    /// Diff position of an added or context line, by target line number
+    pub fn target_line_position(&self, target_line_no: usize) -> Option<usize> {
+        self.positions()
+            .into_iter()
+            .find(|&(_, l)| {
+                (l.is_added() || l.is_context()) && l.target_line_no == Some(target_line_no)
+            })
+            .map(|(p, _)| p)
+    }
+
+    /// Diff position of a line, by its line number in the whole diff
+    pub fn diff_line_position(&self, diff_line_no: usize) -> Option<usize> {
+        self.positions()
+            .into_iter()
+            .find(|&(_, l)| l.diff_line_no == diff_line_no)
+            .map(|(p, _)| p)
+    }
+}
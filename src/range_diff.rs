@@ -0,0 +1,299 @@
+//! Comparison of two series of patches, like `git range-diff`
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::change::{self, Change, CONTEXT};
+use crate::diff::diff;
+use crate::{Hunk, PatchSet};
+
+/// Default percentage of a patch size it costs to drop or add it instead of pairing it
+pub const DEFAULT_CREATION_FACTOR: usize = 60;
+
+/// A patch of either series and how it relates to the other series
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeDiffEntry {
+    /// Index of the patch in the old series
+    pub old: Option<usize>,
+    /// Index of the patch in the new series
+    pub new: Option<usize>,
+    /// Diff from the old to the new patch, empty when they are the same
+    pub hunks: Vec<Hunk>,
+    /// Is this pair in a different order than other pairs
+    pub reordered: bool,
+}
+
+impl RangeDiffEntry {
+    /// Patch only exists in the new series
+    pub fn is_added(&self) -> bool {
+        self.old.is_none()
+    }
+
+    /// Patch only exists in the old series
+    pub fn is_dropped(&self) -> bool {
+        self.new.is_none()
+    }
+
+    /// Patch exists in both series and changed
+    pub fn is_changed(&self) -> bool {
+        self.old.is_some() && self.new.is_some() && !self.hunks.is_empty()
+    }
+
+    /// Patch exists in both series unchanged
+    pub fn is_unchanged(&self) -> bool {
+        self.old.is_some() && self.new.is_some() && self.hunks.is_empty()
+    }
+}
+
+impl fmt::Display for RangeDiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let index = |i: Option<usize>| i.map_or("-".to_owned(), |i| (i + 1).to_string());
+        let status = if self.is_added() {
+            '>'
+        } else if self.is_dropped() {
+            '<'
+        } else if self.is_changed() {
+            '!'
+        } else {
+            '='
+        };
+        write!(f, "{}: {} {}:", index(self.old), status, index(self.new))?;
+        for hunk in &self.hunks {
+            for line in hunk.to_string().lines() {
+                write!(f, "\n    {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pairing of two series of patches, like `git range-diff`
+///
+/// Patches are paired up by the size of the diff between them. A patch whose closest
+/// counterpart differs by more than the creation factor percentage of its own size is
+/// reported as dropped or added instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeDiff {
+    entries: Vec<RangeDiffEntry>,
+}
+
+impl RangeDiff {
+    /// Compare two series of patches with the default creation factor
+    pub fn new(old: &[PatchSet], new: &[PatchSet]) -> RangeDiff {
+        RangeDiff::with_creation_factor(old, new, DEFAULT_CREATION_FACTOR)
+    }
+
+    /// Compare two series of patches with a creation factor percentage
+    pub fn with_creation_factor(
+        old: &[PatchSet],
+        new: &[PatchSet],
+        creation_factor: usize,
+    ) -> RangeDiff {
+        let old_texts: Vec<Vec<String>> = old.iter().map(text).collect();
+        let new_texts: Vec<Vec<String>> = new.iter().map(text).collect();
+        let (a, b) = (old.len(), new.len());
+
+        let n = a + b;
+        let mut cost = vec![0usize; n * n];
+        for i in 0..a {
+            for j in 0..b {
+                cost[i * n + j] = diff(&old_texts[i], &new_texts[j])
+                    .iter()
+                    .map(|(o, n)| o.len() + n.len())
+                    .sum();
+            }
+            for j in b..n {
+                cost[i * n + j] = old_texts[i].len() * creation_factor / 100;
+            }
+        }
+        for i in a..n {
+            for j in 0..b {
+                cost[i * n + j] = new_texts[j].len() * creation_factor / 100;
+            }
+        }
+        let assigned = assignment(n, &cost);
+        let old_matching: Vec<Option<usize>> = (0..a)
+            .map(|i| Some(assigned[i]).filter(|&j| j < b))
+            .collect();
+        let mut new_matching = vec![None; b];
+        for (i, j) in old_matching.iter().enumerate() {
+            if let Some(j) = *j {
+                new_matching[j] = Some(i);
+            }
+        }
+
+        let entry = |old: Option<usize>, new: Option<usize>| {
+            let hunks = match (old, new) {
+                (Some(i), Some(j)) => hunks(&old_texts[i], &new_texts[j]),
+                _ => vec![],
+            };
+            let reordered = match (old, new) {
+                (Some(i), Some(j)) => old_matching
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(k, l)| l.map(|l| (k, l)))
+                    .any(|(k, l)| (k < i) != (l < j) && k != i),
+                _ => false,
+            };
+            RangeDiffEntry {
+                old,
+                new,
+                hunks,
+                reordered,
+            }
+        };
+
+        // same order as git: dropped patches are shown once their predecessors are
+        let mut entries = vec![];
+        let mut shown = vec![false; a];
+        let (mut i, mut j) = (0, 0);
+        while i < a || j < b {
+            while i < a && shown[i] {
+                i += 1;
+            }
+            if i < a && old_matching[i].is_none() {
+                entries.push(entry(Some(i), None));
+                i += 1;
+                continue;
+            }
+            while j < b && new_matching[j].is_none() {
+                entries.push(entry(None, Some(j)));
+                j += 1;
+            }
+            if j < b {
+                let matching = new_matching[j];
+                entries.push(entry(matching, Some(j)));
+                if let Some(matching) = matching {
+                    shown[matching] = true;
+                }
+                j += 1;
+            }
+        }
+        RangeDiff { entries }
+    }
+
+    /// Entries of both series, in display order
+    pub fn entries(&self) -> &[RangeDiffEntry] {
+        &self.entries
+    }
+
+    /// Count of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for RangeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self
+            .entries
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+        write!(f, "{}", entries)
+    }
+}
+
+impl IntoIterator for RangeDiff {
+    type Item = RangeDiffEntry;
+    type IntoIter = ::std::vec::IntoIter<RangeDiffEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// Text of a patch compared by range diff, without line numbers
+fn text(patch: &PatchSet) -> Vec<String> {
+    let mut lines = vec![];
+    for file in patch.files() {
+        lines.push(format!("## {} ##", file.path()));
+        for hunk in file.hunks() {
+            lines.push(format!("@@ {}", hunk.section_header).trim_end().to_owned());
+            lines.extend(hunk.lines().iter().map(|l| l.to_string()));
+        }
+    }
+    lines
+}
+
+/// Hunks turning one patch text into another
+fn hunks(old: &[String], new: &[String]) -> Vec<Hunk> {
+    let changes: Vec<Change> = diff(old, new)
+        .into_iter()
+        .map(|(o, n)| Change::new(o.start + 1, old[o].to_vec(), new[n].to_vec()))
+        .collect();
+    let known: BTreeMap<usize, String> = old
+        .iter()
+        .enumerate()
+        .map(|(n, l)| (n + 1, l.clone()))
+        .collect();
+    change::hunks(&changes, &known, None, CONTEXT)
+}
+
+/// Minimal cost assignment of rows to columns of a square cost matrix
+fn assignment(n: usize, cost: &[usize]) -> Vec<usize> {
+    let inf = i64::MAX / 4;
+    let cost = |i: usize, j: usize| cost[(i - 1) * n + j - 1] as i64;
+    let mut u = vec![0i64; n + 1];
+    let mut v = vec![0i64; n + 1];
+    // row assigned to each column, 1-based with 0 as unassigned
+    let mut p = vec![0usize; n + 1];
+    let mut way = vec![0usize; n + 1];
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0;
+        let mut min_v = vec![inf; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = inf;
+            let mut j1 = 0;
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+                let current = cost(i0, j) - u[i0] - v[j];
+                if current < min_v[j] {
+                    min_v[j] = current;
+                    way[j] = j0;
+                }
+                if min_v[j] < delta {
+                    delta = min_v[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=n {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_v[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+    let mut assigned = vec![0; n];
+    for j in 1..=n {
+        if p[j] > 0 {
+            assigned[p[j] - 1] = j - 1;
+        }
+    }
+    assigned
+}
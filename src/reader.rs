@@ -0,0 +1,233 @@
+//! Parsing diffs from readers, one file at a time
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::visit::{hunk_header, HunkBody};
+use crate::{
+    Error, PatchSet, PatchedFile, Result, RE_BINARY_FILES, RE_DIFF_GIT_HEADER, RE_HUNK_HEADER,
+    RE_SOURCE_FILENAME,
+};
+
+/// Where the reader is in the file being read
+#[derive(Debug, Default)]
+struct FileState {
+    /// The file started with a `diff --git` line
+    git_header: bool,
+    /// The `---` line of the file was read
+    source_file: bool,
+    /// A hunk header of the file was read
+    hunks: bool,
+    /// Lines read of the current hunk
+    hunk: Option<HunkBody>,
+}
+
+/// Parser of the files of a diff read from a `BufRead`, yielding one `PatchedFile` at a time
+///
+/// Only the text of the file being parsed is kept in memory, so diffs of any size, like the
+/// output of `git log -p`, can be processed in a pipeline. Text between files, like commit
+/// messages, is kept with the file that follows it, and text after the last file is
+/// given by `trailer`. After an error, reading goes on with the next file.
+///
+/// ```
+/// use unidiff::PatchReader;
+///
+/// let diff = "--- a/a\n+++ b/a\n@@ -1 +1 @@\n-a\n+b\n--- a/b\n+++ b/b\n@@ -1 +1 @@\n-c\n+d\n";
+/// let names: Vec<String> = PatchReader::new(diff.as_bytes())
+///     .map(|file| file.unwrap().path())
+///     .collect();
+/// assert_eq!(vec!["a", "b"], names);
+/// ```
+#[derive(Debug)]
+pub struct PatchReader<R> {
+    reader: R,
+    patch: PatchSet,
+    /// Text of the file being read
+    chunk: String,
+    /// Lines read before the file being read
+    line_count: usize,
+    state: FileState,
+    peeked: Option<String>,
+    files: VecDeque<PatchedFile>,
+    done: bool,
+}
+
+impl<R: BufRead> PatchReader<R> {
+    /// Initialize a new PatchReader reading from `reader`
+    pub fn new(reader: R) -> PatchReader<R> {
+        PatchReader {
+            reader,
+            patch: PatchSet::new(),
+            chunk: String::new(),
+            line_count: 0,
+            state: FileState::default(),
+            peeked: None,
+            files: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Initialize a new PatchReader decoding its input with `coding`
+    #[cfg(feature = "encoding")]
+    pub fn with_encoding(reader: R, coding: &'static encoding_rs::Encoding) -> PatchReader<R> {
+        let mut patch_reader = PatchReader::new(reader);
+        patch_reader.patch = PatchSet::with_encoding(coding);
+        patch_reader
+    }
+
+    /// Set whether to ignore hunk header lengths when parsing, like `PatchSet::set_lenient`
+    pub fn lenient(mut self, lenient: bool) -> PatchReader<R> {
+        self.patch.set_lenient(lenient);
+        self
+    }
+
+    /// Set whether to keep the text of files for `to_lossless_string`, like
+    /// `PatchSet::set_lossless`
+    pub fn lossless(mut self, lossless: bool) -> PatchReader<R> {
+        self.patch.set_lossless(lossless);
+        self
+    }
+
+    /// Text found after the last file, once every file was read
+    ///
+    /// It is empty until the reader is exhausted.
+    pub fn trailer(&self) -> &str {
+        if self.done {
+            self.patch.trailer()
+        } else {
+            ""
+        }
+    }
+
+    /// Next line of the input, with its line ending
+    fn read_line(&mut self) -> Result<Option<String>> {
+        if let Some(line) = self.peeked.take() {
+            return Ok(Some(line));
+        }
+        let mut buf = vec![];
+        match self.reader.read_until(b'\n', &mut buf) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(self.decode(&buf))),
+            Err(e) => Err(Error::Io(e.to_string())),
+        }
+    }
+
+    /// Line after the last one read, without its line ending
+    fn peek_line(&mut self) -> Result<Option<&str>> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_line()?;
+        }
+        Ok(self.peeked.as_deref().map(trim_line_ending))
+    }
+
+    #[cfg(feature = "encoding")]
+    fn decode(&self, buf: &[u8]) -> String {
+        self.patch.encoding.decode(buf).0.into_owned()
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn decode(&self, buf: &[u8]) -> String {
+        String::from_utf8_lossy(buf).into_owned()
+    }
+
+    /// Does this line start a new file, updating the state of the file being read
+    fn starts_file(&mut self, line: &str) -> Result<bool> {
+        let line = trim_line_ending(line);
+        if self.state.hunk.is_some() {
+            let next = if self.patch.is_lenient() {
+                self.peek_line()?.map(str::to_owned)
+            } else {
+                None
+            };
+            if let Some(ref mut hunk) = self.state.hunk {
+                if hunk.push(line, next.as_deref()) {
+                    return Ok(false);
+                }
+            }
+            self.state.hunk = None;
+        }
+
+        let starts_file = if RE_DIFF_GIT_HEADER.is_match(line) {
+            true
+        } else if RE_BINARY_FILES.is_match(line) {
+            !(self.state.git_header && !self.state.source_file && !self.state.hunks)
+        } else if RE_SOURCE_FILENAME.is_match(line) {
+            !self.state.git_header
+        } else {
+            false
+        };
+        if starts_file {
+            self.state = FileState {
+                git_header: RE_DIFF_GIT_HEADER.is_match(line),
+                ..FileState::default()
+            };
+        }
+        if RE_SOURCE_FILENAME.is_match(line) {
+            self.state.source_file = true;
+        } else if RE_HUNK_HEADER.is_match(line) {
+            self.state.hunks = true;
+            self.state.hunk = Some(HunkBody::new(&hunk_header(line), self.patch.is_lenient()));
+        }
+        Ok(starts_file)
+    }
+
+    /// Parse the text of the file read so far
+    fn parse_chunk(&mut self) -> Result<()> {
+        let chunk = std::mem::take(&mut self.chunk);
+        // lines are numbered from the start of the input, not of the chunk
+        let parsed = self.patch.parse_str(&chunk, self.line_count);
+        self.files.extend(self.patch.files.drain(..));
+        self.line_count += chunk.lines().count();
+        parsed
+    }
+}
+
+/// Line without its line ending, like `str::lines` gives
+fn trim_line_ending(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+impl<R: BufRead> Iterator for PatchReader<R> {
+    type Item = Result<PatchedFile>;
+
+    fn next(&mut self) -> Option<Result<PatchedFile>> {
+        loop {
+            if let Some(file) = self.files.pop_front() {
+                return Some(Ok(file));
+            }
+            if self.done {
+                return None;
+            }
+            let line = match self.read_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    self.done = true;
+                    if let Err(e) = self.parse_chunk() {
+                        return Some(Err(e));
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            let starts_file = match self.starts_file(&line) {
+                Ok(starts_file) => starts_file,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            let parsed = if starts_file {
+                self.parse_chunk()
+            } else {
+                Ok(())
+            };
+            self.chunk.push_str(&line);
+            if let Err(e) = parsed {
+                return Some(Err(e));
+            }
+        }
+    }
+}
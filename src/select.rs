@@ -0,0 +1,88 @@
+//! Partial hunks built from a selection of their lines
+use crate::{Hunk, Line, LineType};
+
+/// A change line with the markers following it, and its index in the hunk
+type Unit<'a> = (usize, Vec<&'a Line>);
+
+impl Hunk {
+    /// Hunk with only the selected changes, to stage them like `git add -p`
+    ///
+    /// `selected` are indices of lines of this hunk. Added lines not selected are dropped
+    /// and removed lines not selected become context. Target line numbers still account for
+    /// the changes of previous hunks, use `PatchedFile::recount` after selecting lines in
+    /// several hunks of a file.
+    pub fn select(&self, selected: &[usize]) -> Hunk {
+        self.select_lines(selected, false)
+    }
+
+    /// Hunk undoing only the selected changes once applied, to unstage them like `git reset -p`
+    ///
+    /// This is `select` for a hunk applied in reverse: removed lines not selected are dropped
+    /// and added lines not selected become context.
+    pub fn select_reverse(&self, selected: &[usize]) -> Hunk {
+        self.select_lines(selected, true)
+    }
+
+    fn select_lines(&self, selected: &[usize], reverse: bool) -> Hunk {
+        let mut lines = vec![];
+        let mut run: Vec<Unit> = vec![];
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.is_added() || line.is_removed() {
+                run.push((i, vec![line]));
+            } else if line.is_context() {
+                select_run(&mut lines, &run, selected, reverse);
+                run.clear();
+                lines.push(line.clone());
+            } else if let Some(unit) = run.last_mut() {
+                unit.1.push(line);
+            } else {
+                lines.push(line.clone());
+            }
+        }
+        select_run(&mut lines, &run, selected, reverse);
+        Hunk::from_lines(
+            self.source_first(),
+            self.target_first(),
+            self.section_header.clone(),
+            lines,
+        )
+    }
+}
+
+/// Append the selected lines of a run of changes
+///
+/// Lines of the side kept in full are turned into context unless selected, and the
+/// selected lines of the other side go next to the selected lines of the kept side.
+fn select_run(lines: &mut Vec<Line>, run: &[Unit], selected: &[usize], reverse: bool) {
+    let is_kept = |unit: &&Unit| unit.1[0].is_added() == reverse;
+    let picked: Vec<Line> = run
+        .iter()
+        .filter(|u| !is_kept(u) && selected.contains(&u.0))
+        .flat_map(|u| u.1.iter().map(|&l| l.clone()))
+        .collect();
+    let kept: Vec<&Unit> = run.iter().filter(is_kept).collect();
+    let position = if reverse {
+        kept.iter()
+            .position(|u| selected.contains(&u.0))
+            .unwrap_or(0)
+    } else {
+        kept.iter()
+            .rposition(|u| selected.contains(&u.0))
+            .map_or(kept.len(), |p| p + 1)
+    };
+    for (i, unit) in kept.iter().enumerate() {
+        if i == position {
+            lines.extend(picked.iter().cloned());
+        }
+        for (j, &line) in unit.1.iter().enumerate() {
+            let mut line = line.clone();
+            if j == 0 && !selected.contains(&unit.0) {
+                line.line_type = LineType::Context;
+            }
+            lines.push(line);
+        }
+    }
+    if position == kept.len() {
+        lines.extend(picked);
+    }
+}
@@ -0,0 +1,37 @@
+//! Serialization of the diff model, with the `serde` feature
+use serde::Deserialize;
+
+use crate::{Hunk, Line};
+
+/// Fields of a serialized hunk, its `added` and `removed` counts are rebuilt from its lines
+#[derive(Deserialize)]
+pub(crate) struct HunkData {
+    source_start: usize,
+    source_length: usize,
+    target_start: usize,
+    target_length: usize,
+    section_header: String,
+    lines: Vec<Line>,
+}
+
+impl From<HunkData> for Hunk {
+    fn from(data: HunkData) -> Hunk {
+        let mut hunk = Hunk::new(
+            data.source_start,
+            data.source_length,
+            data.target_start,
+            data.target_length,
+            data.section_header,
+        );
+        for line in data.lines {
+            hunk.append(line);
+        }
+        hunk
+    }
+}
+
+/// Encoding of deserialized patch sets, which are already decoded
+#[cfg(feature = "encoding")]
+pub(crate) fn default_encoding() -> &'static encoding_rs::Encoding {
+    encoding_rs::UTF_8
+}
@@ -0,0 +1,282 @@
+//! Printing patch sets in two columns, the source on the left and the target on the right
+use std::fmt;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{Hunk, Line, LineType, PatchSet, PatchedFile};
+
+/// Default total width of a side-by-side view, as used by `diff --side-by-side`
+pub const DEFAULT_SIDE_BY_SIDE_WIDTH: usize = 130;
+
+/// Separator of the two columns
+const SEPARATOR: &str = " | ";
+
+impl Hunk {
+    /// Lines of this hunk side by side, the source line on the left and the target line on
+    /// the right
+    ///
+    /// Context lines are on both sides. Removed lines are paired with the added lines
+    /// following them, and the lines left over have nothing on the other side. A "No newline
+    /// at end of file" line stays on the side of the line it follows.
+    pub fn paired_lines(&self) -> Vec<(Option<&Line>, Option<&Line>)> {
+        let mut pairs = vec![];
+        let (mut removed, mut added): (Vec<&Line>, Vec<&Line>) = (vec![], vec![]);
+        let mut last_added = false;
+        let mut last_removed = false;
+        for line in &self.lines {
+            if line.is_removed() || (last_removed && line.line_type == LineType::NoNewlineMarker) {
+                if !added.is_empty() {
+                    pair_runs(&mut pairs, &mut removed, &mut added);
+                }
+                removed.push(line);
+                last_removed = true;
+                last_added = false;
+            } else if line.is_added() || (last_added && line.line_type == LineType::NoNewlineMarker)
+            {
+                added.push(line);
+                last_added = true;
+                last_removed = false;
+            } else {
+                pair_runs(&mut pairs, &mut removed, &mut added);
+                pairs.push((Some(line), Some(line)));
+                last_added = false;
+                last_removed = false;
+            }
+        }
+        pair_runs(&mut pairs, &mut removed, &mut added);
+        pairs
+    }
+}
+
+/// Pair removed lines with added lines, emptying both
+fn pair_runs<'a>(
+    pairs: &mut Vec<(Option<&'a Line>, Option<&'a Line>)>,
+    removed: &mut Vec<&'a Line>,
+    added: &mut Vec<&'a Line>,
+) {
+    for i in 0..removed.len().max(added.len()) {
+        pairs.push((removed.get(i).cloned(), added.get(i).cloned()));
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// A patch set printed in two columns, like `diff --side-by-side` with line numbers
+///
+/// Each file starts with its source and target names, and each hunk with its header. Lines
+/// too long for their column are truncated with `…`, or wrapped on several rows.
+#[derive(Debug, Clone)]
+pub struct SideBySide<'a> {
+    patch: &'a PatchSet,
+    width: usize,
+    wrap: bool,
+    tab_width: usize,
+}
+
+impl<'a> SideBySide<'a> {
+    /// Initialize a new SideBySide printing `patch` in 130 columns
+    pub fn new(patch: &'a PatchSet) -> SideBySide<'a> {
+        SideBySide {
+            patch,
+            width: DEFAULT_SIDE_BY_SIDE_WIDTH,
+            wrap: false,
+            tab_width: 8,
+        }
+    }
+
+    /// Total width of the view
+    pub fn width(mut self, width: usize) -> SideBySide<'a> {
+        self.width = width;
+        self
+    }
+
+    /// Wrap long lines instead of truncating them
+    pub fn wrap(mut self, wrap: bool) -> SideBySide<'a> {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Columns between tab stops, tabs are expanded to spaces
+    pub fn tab_width(mut self, tab_width: usize) -> SideBySide<'a> {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
+    fn fmt_file(&self, f: &mut fmt::Formatter, file: &PatchedFile) -> fmt::Result {
+        let last_line_no = file
+            .hunks()
+            .iter()
+            .map(|h| (h.source_start + h.source_length).max(h.target_start + h.target_length))
+            .max()
+            .unwrap_or(0);
+        let number_width = last_line_no.to_string().len();
+        // line number, space, line type and text on each side of the separator
+        let text_width = (self.width.saturating_sub(SEPARATOR.len()) / 2)
+            .saturating_sub(number_width + 2)
+            .max(1);
+        let side_width = number_width + 2 + text_width;
+
+        writeln!(
+            f,
+            "{}",
+            row(
+                &fit(&file.source_file, side_width),
+                &fit(&file.target_file, side_width)
+            )
+        )?;
+        for hunk in file.hunks() {
+            let mut header = hunk.to_git_string();
+            header.truncate(header.find('\n').unwrap_or(header.len()));
+            writeln!(f, "{}", fit(&header, self.width).trim_end())?;
+
+            for (source, target) in hunk.paired_lines() {
+                let left = self.cells(source, |l| l.source_line_no, number_width, text_width);
+                let right = self.cells(target, |l| l.target_line_no, number_width, text_width);
+                let blank = " ".repeat(side_width);
+                for i in 0..left.len().max(right.len()) {
+                    writeln!(
+                        f,
+                        "{}",
+                        row(
+                            left.get(i).unwrap_or(&blank),
+                            right.get(i).unwrap_or(&blank)
+                        )
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rows of one side of a line, numbered on the first one
+    fn cells(
+        &self,
+        line: Option<&Line>,
+        line_no: fn(&Line) -> Option<usize>,
+        number_width: usize,
+        text_width: usize,
+    ) -> Vec<String> {
+        let line = match line {
+            Some(line) => line,
+            None => return vec![],
+        };
+        let number = line_no(line).map(|n| n.to_string()).unwrap_or_default();
+        let text = expand_tabs(&line.value, self.tab_width);
+        let chunks = if self.wrap {
+            wrap(&text, text_width)
+        } else {
+            vec![fit(&text, text_width)]
+        };
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                if i == 0 {
+                    format!(
+                        "{:>width$} {}{}",
+                        number,
+                        line.line_type,
+                        chunk,
+                        width = number_width
+                    )
+                } else {
+                    format!("{:>width$}  {}", "", chunk, width = number_width)
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'a> fmt::Display for SideBySide<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for file in self.patch.files() {
+            self.fmt_file(f, file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Both sides of a row, without trailing spaces
+fn row(left: &str, right: &str) -> String {
+    format!("{}{}{}", left, SEPARATOR, right)
+        .trim_end()
+        .to_owned()
+}
+
+/// Text with tabs replaced by spaces up to the next tab stop
+fn expand_tabs(text: &str, tab_width: usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for c in text.chars() {
+        if c == '\t' {
+            let spaces = tab_width - column % tab_width;
+            expanded.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            expanded.push(c);
+            column += c.width().unwrap_or(0);
+        }
+    }
+    expanded
+}
+
+/// Text padded with spaces to `width` columns, or truncated with `…`
+fn fit(text: &str, width: usize) -> String {
+    let text_width = text.width();
+    if text_width <= width {
+        return format!("{}{}", text, " ".repeat(width - text_width));
+    }
+    let mut fitted = String::new();
+    let mut column = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if column + w + 1 > width {
+            break;
+        }
+        fitted.push(c);
+        column += w;
+    }
+    fitted.push('…');
+    column += 1;
+    fitted.push_str(&" ".repeat(width.saturating_sub(column)));
+    fitted
+}
+
+/// Text cut in chunks of `width` columns, padded with spaces
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    let mut column = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if column + w > width && !chunk.is_empty() {
+            chunks.push(fit(&chunk, width));
+            chunk.clear();
+            column = 0;
+        }
+        chunk.push(c);
+        column += w;
+    }
+    chunks.push(fit(&chunk, width));
+    chunks
+}
+
+impl PatchSet {
+    /// This patch set printed in two columns
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n".parse().unwrap();
+    /// assert_eq!(
+    ///     "a/f       | b/f\n\
+    ///      @@ -1,2 +1,2 @@\n\
+    ///      1  a      | 1  a\n\
+    ///      2 -b      | 2 +c\n",
+    ///     patch.side_by_side().width(21).to_string()
+    /// );
+    /// ```
+    pub fn side_by_side(&self) -> SideBySide<'_> {
+        SideBySide::new(self)
+    }
+}
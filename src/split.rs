@@ -0,0 +1,103 @@
+//! Splitting and merging of hunks
+use crate::{Error, Hunk, Line, Result};
+
+impl Hunk {
+    /// Source and target line numbers each line of this hunk starts at
+    fn positions(&self) -> Vec<(usize, usize)> {
+        let mut source_line_no = self.source_first();
+        let mut target_line_no = self.target_first();
+        let mut positions = Vec::with_capacity(self.lines.len());
+        for line in &self.lines {
+            positions.push((source_line_no, target_line_no));
+            if line.is_context() || line.is_removed() {
+                source_line_no += 1;
+            }
+            if line.is_context() || line.is_added() {
+                target_line_no += 1;
+            }
+        }
+        positions
+    }
+
+    /// Split this hunk into the smallest hunks separated by context lines
+    ///
+    /// Like `git add -p` does, each hunk keeps all the context lines before and after its
+    /// changes, so context lines between two changes end up in both hunks.
+    pub fn split(&self) -> Vec<Hunk> {
+        // ranges of change lines, with the markers following them
+        let mut runs: Vec<(usize, usize)> = vec![];
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.is_context() {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(run) if run.1 == i => run.1 = i + 1,
+                _ if !line.is_added() && !line.is_removed() => {}
+                _ => runs.push((i, i + 1)),
+            }
+        }
+        if runs.len() < 2 {
+            return vec![self.clone()];
+        }
+
+        let positions = self.positions();
+        let mut hunks = vec![];
+        for i in 0..runs.len() {
+            let first = if i == 0 { 0 } else { runs[i - 1].1 };
+            let last = runs.get(i + 1).map_or(self.lines.len(), |r| r.0);
+            let (source_first, target_first) = positions[first];
+            hunks.push(Hunk::from_lines(
+                source_first,
+                target_first,
+                self.section_header.clone(),
+                self.lines[first..last].to_vec(),
+            ));
+        }
+        hunks
+    }
+
+    /// Merge this hunk with an adjacent or overlapping one
+    ///
+    /// Lines shared by both hunks must be context lines with the same content.
+    pub fn merge(&self, other: &Hunk) -> Result<Hunk> {
+        let (first, second) = if other.source_first() < self.source_first() {
+            (other, self)
+        } else {
+            (self, other)
+        };
+        let first_end = first.source_first() + first.source_length;
+        if second.source_first() > first_end {
+            return Err(Error::Mismatch(format!(
+                "hunks at lines {} and {} are neither adjacent nor overlapping",
+                first.source_first(),
+                second.source_first()
+            )));
+        }
+        let overlap = first_end - second.source_first();
+        let shared = first.lines.len().saturating_sub(overlap);
+        let first_shared: &[Line] = &first.lines[shared..];
+        let second_shared: &[Line] = &second.lines[..overlap.min(second.lines.len())];
+        if first_shared.len() != overlap
+            || second_shared.len() != overlap
+            || first_shared
+                .iter()
+                .zip(second_shared)
+                .any(|(a, b)| !a.is_context() || !b.is_context() || a.value != b.value)
+        {
+            return Err(Error::Mismatch(format!(
+                "hunks at lines {} and {} share {} lines that differ or are not context",
+                first.source_first(),
+                second.source_first(),
+                overlap
+            )));
+        }
+        let mut lines = first.lines.clone();
+        lines.extend_from_slice(&second.lines[overlap..]);
+        Ok(Hunk::from_lines(
+            first.source_first(),
+            first.target_first(),
+            first.section_header.clone(),
+            lines,
+        ))
+    }
+}
@@ -0,0 +1,527 @@
+//! Summaries of the changes of a patch set, like `git diff --stat`
+use std::fmt;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::paths::names;
+use crate::{ExtendedHeader, PatchSet, PatchedFile};
+
+/// Default total width of a diffstat, as used by git outside of a terminal
+pub const DEFAULT_STAT_WIDTH: usize = 80;
+
+/// Changes of a patched file, as counted by diffstat
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileStat {
+    /// File name, with `{old => new}` for renamed and copied files
+    pub name: String,
+    /// Count of lines added
+    pub added: usize,
+    /// Count of lines removed
+    pub removed: usize,
+    /// Is this file binary
+    pub binary: bool,
+}
+
+impl FileStat {
+    /// Initialize a new FileStat counting the changes of `file`
+    pub fn new(file: &PatchedFile) -> FileStat {
+        FileStat {
+            name: stat_name(file),
+            added: file.added(),
+            removed: file.removed(),
+            binary: file.is_binary_file(),
+        }
+    }
+}
+
+/// File name of a patched file in a diffstat, without the git `a/` and `b/` prefixes
+fn stat_name(file: &PatchedFile) -> String {
+    let mut renamed = (None, None);
+    for header in file.extended_headers() {
+        match *header {
+            ExtendedHeader::RenameFrom(ref p) | ExtendedHeader::CopyFrom(ref p) => {
+                renamed.0 = Some(p.as_str())
+            }
+            ExtendedHeader::RenameTo(ref p) | ExtendedHeader::CopyTo(ref p) => {
+                renamed.1 = Some(p.as_str())
+            }
+            _ => {}
+        }
+    }
+    if let (Some(source), Some(target)) = renamed {
+        return pprint_rename(source, target);
+    }
+
+    let (source, target) = names(file);
+    if file.is_renamed_file() {
+        pprint_rename(source, target)
+    } else if source == "/dev/null" {
+        target.to_owned()
+    } else {
+        source.to_owned()
+    }
+}
+
+/// Name of a renamed file, sharing the common leading and trailing directories of both names
+fn pprint_rename(a: &str, b: &str) -> String {
+    let (a_bytes, b_bytes) = (a.as_bytes(), b.as_bytes());
+    let mut pfx_length = 0;
+    for (i, (x, y)) in a_bytes.iter().zip(b_bytes).enumerate() {
+        if x != y {
+            break;
+        }
+        if *x == b'/' {
+            pfx_length = i + 1;
+        }
+    }
+
+    // a common prefix ends with a slash, the suffix may start with that same slash
+    let pfx_adjust_for_slash = if pfx_length > 0 { 1 } else { 0 };
+    let mut sfx_length = 0;
+    let (mut i, mut j) = (a.len() as isize, b.len() as isize);
+    let limit = (pfx_length - pfx_adjust_for_slash) as isize;
+    while limit <= i && limit <= j && a_bytes.get(i as usize) == b_bytes.get(j as usize) {
+        if a_bytes.get(i as usize) == Some(&b'/') {
+            sfx_length = a.len() - i as usize;
+        }
+        i -= 1;
+        j -= 1;
+    }
+
+    let a_mid = &a[pfx_length..a.len().saturating_sub(sfx_length).max(pfx_length)];
+    let b_mid = &b[pfx_length..b.len().saturating_sub(sfx_length).max(pfx_length)];
+    if pfx_length + sfx_length > 0 {
+        format!(
+            "{}{{{} => {}}}{}",
+            &a[..pfx_length],
+            a_mid,
+            b_mid,
+            &a[a.len() - sfx_length..]
+        )
+    } else {
+        format!("{} => {}", a_mid, b_mid)
+    }
+}
+
+/// Count of decimal digits of `n`
+fn decimal_width(n: usize) -> usize {
+    n.to_string().len()
+}
+
+/// Scale a change count to the graph width, showing at least one column for any change
+fn scale_linear(it: usize, width: usize, max_change: usize) -> usize {
+    if it == 0 {
+        0
+    } else {
+        1 + it * (width - 1) / max_change
+    }
+}
+
+/// Per-file changes of a patch set with histogram bars, like `git diff --stat`
+///
+/// ```
+/// use unidiff::PatchSet;
+///
+/// let patch: PatchSet = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(
+///     " src/lib.rs | 2 +-\n 1 file changed, 1 insertion(+), 1 deletion(-)",
+///     patch.diffstat().to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diffstat {
+    files: Vec<FileStat>,
+    width: usize,
+    name_width: usize,
+    graph_width: usize,
+}
+
+impl Diffstat {
+    /// Initialize a new Diffstat of `patch`, 80 columns wide
+    pub fn new(patch: &PatchSet) -> Diffstat {
+        Diffstat {
+            files: patch.files().iter().map(FileStat::new).collect(),
+            width: DEFAULT_STAT_WIDTH,
+            name_width: 0,
+            graph_width: 0,
+        }
+    }
+
+    /// Set the total width, like `--stat-width`
+    pub fn width(mut self, width: usize) -> Diffstat {
+        self.width = width;
+        self
+    }
+
+    /// Limit the width of file names, like `--stat-name-width`, 0 for no limit
+    pub fn name_width(mut self, width: usize) -> Diffstat {
+        self.name_width = width;
+        self
+    }
+
+    /// Limit the width of the histogram, like `--stat-graph-width`, 0 for no limit
+    pub fn graph_width(mut self, width: usize) -> Diffstat {
+        self.graph_width = width;
+        self
+    }
+
+    /// Changes of each file
+    pub fn files(&self) -> &[FileStat] {
+        &self.files
+    }
+
+    /// Count of lines added, binary files excluded
+    pub fn added(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|f| !f.binary)
+            .map(|f| f.added)
+            .sum()
+    }
+
+    /// Count of lines removed, binary files excluded
+    pub fn removed(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|f| !f.binary)
+            .map(|f| f.removed)
+            .sum()
+    }
+
+    /// Per-file counts, like `git diff --numstat`
+    ///
+    /// Counts of binary files are `-`.
+    pub fn numstat(&self) -> String {
+        self.files
+            .iter()
+            .map(|f| {
+                if f.binary {
+                    format!("-\t-\t{}", f.name)
+                } else {
+                    format!("{}\t{}\t{}", f.added, f.removed, f.name)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Summary line, like `git diff --shortstat`
+    pub fn summary(&self) -> String {
+        let files = self.files.len();
+        if files == 0 {
+            return " 0 files changed".to_owned();
+        }
+        let (added, removed) = (self.added(), self.removed());
+        let mut summary = format!(
+            " {} file{} changed",
+            files,
+            if files == 1 { "" } else { "s" }
+        );
+        if added > 0 || removed == 0 {
+            summary.push_str(&format!(
+                ", {} insertion{}(+)",
+                added,
+                if added == 1 { "" } else { "s" }
+            ));
+        }
+        if removed > 0 || added == 0 {
+            summary.push_str(&format!(
+                ", {} deletion{}(-)",
+                removed,
+                if removed == 1 { "" } else { "s" }
+            ));
+        }
+        summary
+    }
+}
+
+impl fmt::Display for Diffstat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut max_len = 0;
+        let mut max_change = 0;
+        let mut bin_width = 0;
+        let mut number_width = 0;
+        for file in &self.files {
+            max_len = max_len.max(file.name.width());
+            if file.binary {
+                // "Bin XXX -> YYY bytes", sizes are unknown
+                bin_width = bin_width.max(14 + decimal_width(0) + decimal_width(0));
+                number_width = 3;
+                continue;
+            }
+            max_change = max_change.max(file.added + file.removed);
+        }
+        number_width = number_width.max(decimal_width(max_change));
+
+        // at least 10 columns for names and 6 for the graph
+        let width = self.width.max(16 + 6 + number_width) as isize;
+        let number_width = number_width as isize;
+        let mut graph_width = if max_change + 4 > bin_width {
+            max_change
+        } else {
+            bin_width - 4
+        } as isize;
+        if self.graph_width > 0 && (self.graph_width as isize) < graph_width {
+            graph_width = self.graph_width as isize;
+        }
+        let mut name_width = if self.name_width > 0 && self.name_width < max_len {
+            self.name_width
+        } else {
+            max_len
+        } as isize;
+
+        if name_width + number_width + 6 + graph_width > width {
+            if graph_width > width * 3 / 8 - number_width - 6 {
+                graph_width = (width * 3 / 8 - number_width - 6).max(6);
+            }
+            if self.graph_width > 0 && graph_width > self.graph_width as isize {
+                graph_width = self.graph_width as isize;
+            }
+            if name_width > width - number_width - 6 - graph_width {
+                name_width = width - number_width - 6 - graph_width;
+            } else {
+                graph_width = width - number_width - 6 - name_width;
+            }
+        }
+        let graph_width = graph_width as usize;
+        let number_width = number_width as usize;
+
+        for file in &self.files {
+            // shorten long names from the left, at a directory boundary when possible
+            let mut prefix = "";
+            let mut name = file.name.as_str();
+            let mut len = name_width;
+            if name_width < name.width() as isize {
+                prefix = "...";
+                len = (len - 3).max(0);
+                let mut name_len = name.width() as isize;
+                let mut chars = name.char_indices();
+                while name_len > len {
+                    match chars.next() {
+                        Some((_, c)) => name_len -= c.width().unwrap_or(0) as isize,
+                        None => break,
+                    }
+                }
+                name = chars.as_str();
+                if let Some(slash) = name.find('/') {
+                    name = &name[slash..];
+                }
+            }
+            let padding = " ".repeat((len - name.width() as isize).max(0) as usize);
+
+            if file.binary {
+                writeln!(
+                    f,
+                    " {}{}{} | {:>w$}",
+                    prefix,
+                    name,
+                    padding,
+                    "Bin",
+                    w = number_width
+                )?;
+                continue;
+            }
+
+            let (mut add, mut del) = (file.added, file.removed);
+            if graph_width <= max_change {
+                let mut total = scale_linear(add + del, graph_width, max_change);
+                if total < 2 && add > 0 && del > 0 {
+                    total = 2;
+                }
+                if add < del {
+                    add = scale_linear(add, graph_width, max_change);
+                    del = total - add;
+                } else {
+                    del = scale_linear(del, graph_width, max_change);
+                    add = total - del;
+                }
+            }
+            let changes = file.added + file.removed;
+            writeln!(
+                f,
+                " {}{}{} | {:>w$}{}{}{}",
+                prefix,
+                name,
+                padding,
+                changes,
+                if changes > 0 { " " } else { "" },
+                "+".repeat(add),
+                "-".repeat(del),
+                w = number_width
+            )?;
+        }
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Default smallest share of changes of a directory in a dirstat, in permille
+pub const DEFAULT_DIRSTAT_LIMIT: usize = 30;
+
+/// Share of changes of each directory, like `git diff --dirstat=lines`
+///
+/// Changes are counted in lines added and removed, or in files changed. git counts bytes by
+/// default, which needs the content of the files. A binary file counts for a single line,
+/// as its size is unknown.
+///
+/// ```
+/// use unidiff::PatchSet;
+///
+/// let patch: PatchSet = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(" 100.0% src/", patch.dirstat().to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dirstat {
+    files: Vec<(String, usize)>,
+    by_file: bool,
+    limit: usize,
+    cumulative: bool,
+}
+
+impl Dirstat {
+    /// Initialize a new Dirstat of `patch`, counting lines, with a limit of 3%
+    pub fn new(patch: &PatchSet) -> Dirstat {
+        let mut files: Vec<(String, usize)> = patch
+            .files()
+            .iter()
+            .map(|f| {
+                let (source, target) = names(f);
+                let name = if target == "/dev/null" {
+                    source
+                } else {
+                    target
+                };
+                let damage = if f.is_binary_file() {
+                    1
+                } else {
+                    f.added() + f.removed()
+                };
+                (name.to_owned(), damage)
+            })
+            .collect();
+        files.sort();
+        Dirstat {
+            files,
+            by_file: false,
+            limit: DEFAULT_DIRSTAT_LIMIT,
+            cumulative: false,
+        }
+    }
+
+    /// Set whether to count files changed instead of lines, like `--dirstat=files`
+    pub fn by_file(mut self, by_file: bool) -> Dirstat {
+        self.by_file = by_file;
+        self
+    }
+
+    /// Set the smallest share of changes, in permille, for a directory to be shown
+    ///
+    /// Changes of directories below it are counted in their parent directory.
+    pub fn limit(mut self, permille: usize) -> Dirstat {
+        self.limit = permille;
+        self
+    }
+
+    /// Set whether to count changes of shown directories in their parent directory too
+    pub fn cumulative(mut self, cumulative: bool) -> Dirstat {
+        self.cumulative = cumulative;
+        self
+    }
+
+    /// Directories shown with their share of changes in permille, in path order
+    pub fn entries(&self) -> Vec<(String, usize)> {
+        // files with unchanged content, like pure renames, are not counted
+        let files: Vec<(&str, usize)> = self
+            .files
+            .iter()
+            .map(|&(ref name, damage)| {
+                let damage = if self.by_file { damage.min(1) } else { damage };
+                (name.as_str(), damage)
+            })
+            .collect();
+        let changed: usize = files.iter().map(|f| f.1).sum();
+        let mut entries = vec![];
+        if changed > 0 {
+            self.gather(&mut files.as_slice(), changed, "", &mut entries);
+        }
+        entries
+    }
+
+    /// Gather changes of the files under `base`, consuming them from `files`
+    fn gather(
+        &self,
+        files: &mut &[(&str, usize)],
+        changed: usize,
+        base: &str,
+        entries: &mut Vec<(String, usize)>,
+    ) -> usize {
+        let mut sum_changes = 0;
+        let mut sources = 0;
+        while let Some(&(name, damage)) = files.first() {
+            if !name.starts_with(base) {
+                break;
+            }
+            match name[base.len()..].find('/') {
+                Some(slash) => {
+                    let dir = &name[..base.len() + slash + 1];
+                    sum_changes += self.gather(files, changed, dir, entries);
+                    sources += 1;
+                }
+                None => {
+                    sum_changes += damage;
+                    *files = &files[1..];
+                    sources += 2;
+                }
+            }
+        }
+
+        // neither the top level nor a directory with all changes in one subdirectory
+        if !base.is_empty() && sources != 1 && sum_changes > 0 {
+            let permille = sum_changes * 1000 / changed;
+            if permille >= self.limit {
+                entries.push((base.to_owned(), permille));
+                if !self.cumulative {
+                    return 0;
+                }
+            }
+        }
+        sum_changes
+    }
+}
+
+impl fmt::Display for Dirstat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self
+            .entries()
+            .iter()
+            .map(|&(ref dir, permille)| format!("{:4}.{}% {}", permille / 10, permille % 10, dir))
+            .collect::<Vec<String>>()
+            .join("\n");
+        write!(f, "{}", entries)
+    }
+}
+
+impl PatchSet {
+    /// Per-file changes of this patch set, like `git diff --stat`
+    pub fn diffstat(&self) -> Diffstat {
+        Diffstat::new(self)
+    }
+
+    /// Per-file counts of this patch set, like `git diff --numstat`
+    pub fn numstat(&self) -> String {
+        self.diffstat().numstat()
+    }
+
+    /// Summary of the changes of this patch set, like `git diff --shortstat`
+    pub fn shortstat(&self) -> String {
+        self.diffstat().summary()
+    }
+
+    /// Share of changes of each directory of this patch set, like `git diff --dirstat`
+    pub fn dirstat(&self) -> Dirstat {
+        Dirstat::new(self)
+    }
+}
@@ -0,0 +1,439 @@
+//! Parsing diffs as a sequence of events, without building patch sets
+use std::convert::TryFrom;
+
+use crate::borrowed::LineRef;
+use crate::{
+    Error, ExtendedHeader, LineType, Result, RE_BINARY_FILES, RE_DIFF_GIT_HEADER,
+    RE_HUNK_BODY_LINE, RE_HUNK_HEADER, RE_SOURCE_FILENAME, RE_TARGET_FILENAME,
+};
+
+/// Header starting a file, or naming the file of a `diff --git` line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileHeader<'a> {
+    /// `diff --git` line
+    Git {
+        /// Source file name
+        source_file: &'a str,
+        /// Target file name
+        target_file: &'a str,
+    },
+    /// `---` and `+++` lines, naming the file of the `diff --git` line before them if any
+    Unified {
+        /// Source file name
+        source_file: &'a str,
+        /// Source file timestamp
+        source_timestamp: Option<&'a str>,
+        /// Target file name
+        target_file: &'a str,
+        /// Target file timestamp
+        target_timestamp: Option<&'a str>,
+    },
+    /// `Binary files ... differ` line without a `diff --git` line
+    Binary {
+        /// Source file name
+        source_file: &'a str,
+        /// Target file name
+        target_file: &'a str,
+    },
+}
+
+impl<'a> FileHeader<'a> {
+    /// Source file name
+    pub fn source_file(&self) -> &'a str {
+        match *self {
+            FileHeader::Git { source_file, .. }
+            | FileHeader::Unified { source_file, .. }
+            | FileHeader::Binary { source_file, .. } => source_file,
+        }
+    }
+
+    /// Target file name
+    pub fn target_file(&self) -> &'a str {
+        match *self {
+            FileHeader::Git { target_file, .. }
+            | FileHeader::Unified { target_file, .. }
+            | FileHeader::Binary { target_file, .. } => target_file,
+        }
+    }
+}
+
+/// Ranges and section of a hunk header
+///
+/// When parsing leniently, the lengths are those of the lines of the hunk rather than
+/// those written in the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HunkHeader<'a> {
+    /// Source file starting line number
+    pub source_start: usize,
+    /// Source file changes length
+    pub source_length: usize,
+    /// Target file starting line number
+    pub target_start: usize,
+    /// Target file changes length
+    pub target_length: usize,
+    /// Section header
+    pub section_header: &'a str,
+}
+
+/// Receiver of the parts of a diff, in the order they are parsed
+///
+/// Every method does nothing by default, so visitors only implement the events they need.
+/// `diff_line_no` is the line number of the event in the diff, counting from 1.
+///
+/// ```
+/// use unidiff::{FileHeader, Visitor};
+///
+/// #[derive(Default)]
+/// struct Paths<'a>(Vec<&'a str>);
+///
+/// impl<'a> Visitor<'a> for Paths<'a> {
+///     fn on_file_header(&mut self, header: FileHeader<'a>, _diff_line_no: usize) {
+///         self.0.push(header.target_file());
+///     }
+/// }
+///
+/// let mut paths = Paths::default();
+/// unidiff::visit("--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+b\n", &mut paths).unwrap();
+/// assert_eq!(vec!["b/f"], paths.0);
+/// ```
+pub trait Visitor<'a> {
+    /// A file header, starting a new file unless it names the file of a `diff --git` line
+    fn on_file_header(&mut self, _header: FileHeader<'a>, _diff_line_no: usize) {}
+
+    /// A git extended header of the current file
+    fn on_extended_header(&mut self, _header: ExtendedHeader, _diff_line_no: usize) {}
+
+    /// A hunk header, starting a new hunk of the current file
+    fn on_hunk_header(&mut self, _header: HunkHeader<'a>, _diff_line_no: usize) {}
+
+    /// A line of the current hunk
+    fn on_line(&mut self, _line: LineRef<'a>) {}
+
+    /// A line telling the current file is binary
+    fn on_binary(&mut self, _line: &'a str, _diff_line_no: usize) {}
+
+    /// A line outside of file headers and hunks, like a commit message
+    fn on_garbage(&mut self, _line: &'a str, _diff_line_no: usize) {}
+
+    /// The end of the current file, before the line starting the next one or after the
+    /// last line
+    fn on_file_end(&mut self, _diff_line_no: usize) {}
+}
+
+/// Parse diff from string, sending its parts to `visitor`
+pub fn visit<'a, V: Visitor<'a>>(input: &'a str, visitor: &mut V) -> Result<()> {
+    visit_with(input, 0, false, visitor)
+}
+
+/// Parse diff from string, sending its parts to `visitor` and ignoring hunk header lengths
+/// like `PatchSet::set_lenient`
+pub fn visit_lenient<'a, V: Visitor<'a>>(input: &'a str, visitor: &mut V) -> Result<()> {
+    visit_with(input, 0, true, visitor)
+}
+
+/// Parse diff from string, numbering its lines after the `line_offset` lines before it
+// `flush!` resets the file state, which some of its callers set again right after
+#[allow(unused_assignments)]
+pub(crate) fn visit_with<'a, V: Visitor<'a>>(
+    input: &'a str,
+    line_offset: usize,
+    lenient: bool,
+    visitor: &mut V,
+) -> Result<()> {
+    let diff: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + line_offset, line))
+        .collect();
+
+    let mut in_file = false;
+    let mut hunks_found = false;
+    let mut git_header_found = false;
+    let mut source_file: Option<&'a str> = None;
+    let mut source_timestamp: Option<&'a str> = None;
+
+    macro_rules! flush {
+        ($diff_line_no:expr) => {
+            if in_file {
+                visitor.on_file_end($diff_line_no);
+                in_file = false;
+                hunks_found = false;
+                git_header_found = false;
+                source_file = None;
+                source_timestamp = None;
+            }
+        };
+    }
+
+    let mut i = 0;
+    while i < diff.len() {
+        let (index, line) = diff[i];
+        i += 1;
+        let line_no = index + 1;
+
+        if let Some(captures) = RE_DIFF_GIT_HEADER.captures(line) {
+            flush!(line_no);
+            let header = FileHeader::Git {
+                source_file: captures.name("source_file").unwrap().as_str(),
+                target_file: captures.name("target_file").unwrap().as_str(),
+            };
+            visitor.on_file_header(header, line_no);
+            in_file = true;
+            git_header_found = true;
+            continue;
+        }
+
+        // check for extended headers, only between the git header and the file names
+        if git_header_found && source_file.is_none() && !hunks_found {
+            if let Some(header) = ExtendedHeader::parse(line) {
+                visitor.on_extended_header(header, line_no);
+                continue;
+            }
+            if line == "GIT binary patch" || RE_BINARY_FILES.is_match(line) {
+                visitor.on_binary(line, line_no);
+                continue;
+            }
+        }
+
+        // check for binary files compared without a git header
+        if let Some(captures) = RE_BINARY_FILES.captures(line) {
+            flush!(line_no);
+            let header = FileHeader::Binary {
+                source_file: captures.name("source_file").unwrap().as_str(),
+                target_file: captures.name("target_file").unwrap().as_str(),
+            };
+            visitor.on_file_header(header, line_no);
+            visitor.on_binary(line, line_no);
+            in_file = true;
+            continue;
+        }
+
+        // check for source file header
+        if let Some(captures) = RE_SOURCE_FILENAME.captures(line) {
+            if !git_header_found {
+                flush!(line_no);
+            }
+            source_file = Some(captures.name("filename").map_or("", |f| f.as_str()));
+            source_timestamp = captures.name("timestamp").map(|t| t.as_str());
+            continue;
+        }
+
+        // check for target file header
+        if let Some(captures) = RE_TARGET_FILENAME.captures(line) {
+            let source_file = match source_file {
+                Some(source_file) if git_header_found || !in_file => source_file,
+                _ => {
+                    return Err(Error::TargetWithoutSource(format!(
+                        "line {}: {}",
+                        line_no, line
+                    )))
+                }
+            };
+            let header = FileHeader::Unified {
+                source_file,
+                source_timestamp,
+                target_file: captures.name("filename").map_or("", |f| f.as_str()),
+                target_timestamp: captures.name("timestamp").map(|t| t.as_str()),
+            };
+            visitor.on_file_header(header, line_no);
+            in_file = true;
+            hunks_found = false;
+            continue;
+        }
+
+        // check for hunk header
+        if RE_HUNK_HEADER.is_match(line) {
+            if !in_file {
+                return Err(Error::UnexpectedHunk(line.to_owned()));
+            }
+            i += visit_hunk(line, line_no, &diff[i..], lenient, visitor)?;
+            hunks_found = true;
+            continue;
+        }
+
+        visitor.on_garbage(line, line_no);
+    }
+    flush!(line_offset + diff.len() + 1);
+    Ok(())
+}
+
+/// Could this line continue a hunk, ignoring the hunk header lengths
+fn is_lenient_body_line(line: &str, next: Option<&str>) -> bool {
+    match line.chars().next() {
+        None | Some(' ') | Some('+') | Some('\\') => true,
+        Some('-') => !line.starts_with("--- ") || !matches!(next, Some(n) if n.starts_with("+++ ")),
+        _ => false,
+    }
+}
+
+/// Type of a hunk line, empty lines and unknown types being context lines
+fn body_line_type(line: &str) -> LineType {
+    match line.as_bytes().first() {
+        Some(b'+') => LineType::Added,
+        Some(b'-') => LineType::Removed,
+        Some(b'\\') => LineType::NoNewlineMarker,
+        _ => LineType::Context,
+    }
+}
+
+/// Header of a hunk, from a line matching `RE_HUNK_HEADER`
+pub(crate) fn hunk_header(header: &str) -> HunkHeader<'_> {
+    let header_info = RE_HUNK_HEADER.captures(header).unwrap();
+    let number = |name: &str, default: &str| {
+        header_info
+            .name(name)
+            .map_or(default, |s| s.as_str())
+            .parse::<usize>()
+            .unwrap()
+    };
+    HunkHeader {
+        source_start: number("source_start", "0"),
+        source_length: number("source_length", "1"),
+        target_start: number("target_start", "0"),
+        target_length: number("target_length", "1"),
+        section_header: header_info
+            .name("section_header")
+            .map_or("", |s| s.as_str()),
+    }
+}
+
+/// Lines of a hunk read so far, telling where the hunk ends
+///
+/// Both the parser and `PatchReader` find the end of hunks with it, so they split a diff
+/// the same way.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HunkBody {
+    source_length: usize,
+    target_length: usize,
+    lenient: bool,
+    source_count: usize,
+    target_count: usize,
+    /// All lines were read, only a no newline marker may follow
+    complete: bool,
+    ended: bool,
+}
+
+impl HunkBody {
+    pub(crate) fn new(header: &HunkHeader, lenient: bool) -> HunkBody {
+        HunkBody {
+            source_length: header.source_length,
+            target_length: header.target_length,
+            lenient,
+            source_count: 0,
+            target_count: 0,
+            complete: false,
+            ended: false,
+        }
+    }
+
+    /// Read the next line, returning whether it is part of the hunk
+    ///
+    /// `next` is the line after it, needed in lenient mode. Once a line is not part of the
+    /// hunk, no later line is.
+    pub(crate) fn push(&mut self, line: &str, next: Option<&str>) -> bool {
+        // a no newline marker may follow the last line
+        self.ended = self.ended
+            || (self.complete && !line.starts_with('\\'))
+            || RE_DIFF_GIT_HEADER.is_match(line)
+            || RE_HUNK_HEADER.is_match(line)
+            || (self.lenient && !is_lenient_body_line(line, next));
+        if self.ended {
+            return false;
+        }
+        match body_line_type(line) {
+            LineType::Added => self.target_count += 1,
+            LineType::Removed => self.source_count += 1,
+            LineType::Context => {
+                self.source_count += 1;
+                self.target_count += 1;
+            }
+            _ => {}
+        }
+        if self.complete {
+            self.ended = true;
+        } else if !self.lenient
+            && self.source_count >= self.source_length
+            && self.target_count >= self.target_length
+        {
+            self.complete = true;
+        }
+        true
+    }
+}
+
+/// Parse a hunk from its header and the following lines, with the count of lines used
+fn visit_hunk<'a, V: Visitor<'a>>(
+    header: &'a str,
+    header_line_no: usize,
+    diff: &[(usize, &'a str)],
+    lenient: bool,
+    visitor: &mut V,
+) -> Result<usize> {
+    let mut hunk = hunk_header(header);
+
+    // find the lines of the hunk first, to count them when the lengths are ignored
+    let mut body = HunkBody::new(&hunk, lenient);
+    let consumed = diff
+        .iter()
+        .enumerate()
+        .take_while(|&(i, &(_, line))| body.push(line, diff.get(i + 1).map(|&(_, l)| l)))
+        .count();
+    let (source_count, target_count) = (body.source_count, body.target_count);
+
+    let mut source_line_no = hunk.source_start;
+    let mut target_line_no = hunk.target_start;
+    if lenient {
+        // lengths and line numbers from the lines, like `Hunk::recount`
+        if hunk.source_length == 0 {
+            source_line_no += 1;
+        }
+        if hunk.target_length == 0 {
+            target_line_no += 1;
+        }
+        hunk.source_length = source_count;
+        hunk.target_length = target_count;
+        if source_count == 0 {
+            hunk.source_start = source_line_no.saturating_sub(1);
+        }
+        if target_count == 0 {
+            hunk.target_start = target_line_no.saturating_sub(1);
+        }
+    }
+    visitor.on_hunk_header(hunk, header_line_no);
+
+    for &(diff_line_no, line) in &diff[..consumed] {
+        let valid_line = match RE_HUNK_BODY_LINE.captures(line) {
+            Some(valid_line) => valid_line,
+            None => return Err(Error::ExpectLine(line.to_owned())),
+        };
+        // empty lines are context lines
+        let line_type = LineType::try_from(valid_line.name("line_type").unwrap().as_str())
+            .unwrap_or(LineType::Context);
+        let mut original_line = LineRef {
+            source_line_no: None,
+            target_line_no: None,
+            diff_line_no: diff_line_no + 1,
+            line_type,
+            value: valid_line.name("value").unwrap().as_str(),
+        };
+        match line_type {
+            LineType::Added => {
+                original_line.target_line_no = Some(target_line_no);
+                target_line_no += 1;
+            }
+            LineType::Removed => {
+                original_line.source_line_no = Some(source_line_no);
+                source_line_no += 1;
+            }
+            LineType::Context => {
+                original_line.target_line_no = Some(target_line_no);
+                target_line_no += 1;
+                original_line.source_line_no = Some(source_line_no);
+                source_line_no += 1;
+            }
+            _ => {}
+        }
+        visitor.on_line(original_line);
+    }
+    Ok(consumed)
+}
@@ -0,0 +1,50 @@
+extern crate unidiff;
+
+use unidiff::{LineType, PatchRef, PatchSet};
+
+#[test]
+fn test_borrowed_same_as_owned() {
+    for diff in [
+        include_str!("fixtures/git.diff"),
+        include_str!("fixtures/sample1.diff"),
+        include_str!("fixtures/stat.diff"),
+        include_str!("fixtures/renames.diff"),
+        include_str!("fixtures/svn.diff"),
+    ]
+    .iter()
+    {
+        let owned: PatchSet = diff.parse().unwrap();
+        let borrowed = PatchRef::parse(diff).unwrap();
+        assert_eq!(owned.len(), borrowed.len());
+        let converted = borrowed.into_owned();
+        assert_eq!(owned.files(), converted.files());
+        assert_eq!(owned.to_lossless_string(), converted.to_lossless_string());
+    }
+}
+
+#[test]
+fn test_borrowed_values_point_into_input() {
+    let diff = include_str!("fixtures/sample1.diff");
+    let patch = PatchRef::parse(diff).unwrap();
+    let input = diff.as_bytes().as_ptr_range();
+    let file = &patch.files()[0];
+    assert!(input.contains(&file.source_file.as_ptr()));
+    for hunk in file.hunks() {
+        for line in hunk.lines() {
+            assert!(input.contains(&line.value.as_ptr()) || line.value.is_empty());
+        }
+    }
+    assert_eq!(1, file.hunks()[0].lines()[0].target_line_no.unwrap());
+    assert_eq!(LineType::Added, file.hunks()[0].lines()[0].line_type);
+}
+
+#[test]
+fn test_borrowed_lenient() {
+    let diff = include_str!("fixtures/hand-edited.diff");
+    let mut owned = PatchSet::new();
+    owned.set_lenient(true);
+    owned.parse(diff).unwrap();
+    let borrowed = PatchRef::parse_lenient(diff).unwrap().into_owned();
+    assert!(borrowed.is_lenient());
+    assert_eq!(owned.files(), borrowed.files());
+}
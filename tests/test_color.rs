@@ -0,0 +1,69 @@
+extern crate unidiff;
+
+use unidiff::{Palette, PatchSet};
+
+#[test]
+fn test_colored_like_git() {
+    let patch: PatchSet = include_str!("fixtures/whitespace.diff").parse().unwrap();
+    assert_eq!(
+        include_str!("fixtures/whitespace.color"),
+        patch.colored().to_string()
+    );
+    assert_eq!(
+        patch.to_git_string(),
+        patch
+            .colored()
+            .palette(Palette {
+                meta: String::new(),
+                frag: String::new(),
+                old: String::new(),
+                new: String::new(),
+                whitespace: String::new(),
+                reset: String::new(),
+                ..Palette::default()
+            })
+            .to_string()
+    );
+}
+
+#[test]
+fn test_colored_sections_and_whitespace() {
+    let patch: PatchSet = "--- a/f.c\n+++ b/f.c\n@@ -1,2 +1,2 @@ int main()\n a\n-b\n+b \n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        "\x1b[1mdiff --git a/f.c b/f.c\x1b[m\n\
+         \x1b[1m--- a/f.c\x1b[m\n\
+         \x1b[1m+++ b/f.c\x1b[m\n\
+         \x1b[36m@@ -1,2 +1,2 @@\x1b[m \x1b[mint main()\x1b[m\n \
+         a\x1b[m\n\
+         \x1b[31m-b\x1b[m\n\
+         \x1b[32m+\x1b[m\x1b[32mb\x1b[m\x1b[41m \x1b[m\n",
+        patch.colored().to_string()
+    );
+    assert!(patch
+        .colored()
+        .whitespace_errors(false)
+        .to_string()
+        .ends_with("\x1b[32m+b \x1b[m\n"));
+}
+
+#[test]
+fn test_colored_intraline() {
+    let patch: PatchSet =
+        "--- a/f\n+++ b/f\n@@ -1,3 +1,2 @@\n-let x = 1;\n-foo\n+let x = 2;\n+bar\n-only removed\n"
+            .parse()
+            .unwrap();
+    let text = patch.colored().intraline(true).to_string();
+    let lines: Vec<&str> = text.lines().skip(4).collect();
+    assert_eq!(
+        vec![
+            "\x1b[31m-let x = \x1b[7m1\x1b[27m;\x1b[m",
+            "\x1b[31m-foo\x1b[m",
+            "\x1b[32m+let x = \x1b[7m2\x1b[27m;\x1b[m",
+            "\x1b[32m+\x1b[m\x1b[32mbar\x1b[m",
+            "\x1b[31m-only removed\x1b[m",
+        ],
+        lines
+    );
+}
@@ -0,0 +1,137 @@
+extern crate unidiff;
+
+use unidiff::{Error, PatchSet};
+
+const FIRST: &str = "--- a/file
++++ b/file
+@@ -1,6 +1,7 @@
+ line1
+ line2
+-line3
++three
++three and a half
+ line4
+ line5
+ line6
+";
+
+const SECOND: &str = "--- a/file
++++ b/file
+@@ -13,7 +13,7 @@
+ line12
+ line13
+ line14
+-line15
++fifteen
+ line16
+ line17
+ line18
+";
+
+#[test]
+fn test_commute() {
+    let first: PatchSet = FIRST.parse().unwrap();
+    let second: PatchSet = SECOND.parse().unwrap();
+
+    let (second, first) = first.commute(&second).unwrap();
+
+    let hunk = &second[0][0];
+    assert_eq!(
+        (12, 7, 12, 7),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!("line15", hunk[3].value);
+    assert_eq!(Some(15), hunk[3].source_line_no);
+    assert!(hunk[3].is_removed());
+
+    let hunk = &first[0][0];
+    assert_eq!(
+        (1, 6, 1, 7),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!(1, hunk.removed());
+    assert_eq!(2, hunk.added());
+}
+
+#[test]
+fn test_commute_independent_files() {
+    let first: PatchSet = FIRST.parse().unwrap();
+    let second: PatchSet = SECOND.replace("/file", "/other").parse().unwrap();
+
+    let (commuted_second, commuted_first) = first.commute(&second).unwrap();
+    assert_eq!(second[0].hunks(), commuted_second[0].hunks());
+    assert_eq!(first[0].hunks(), commuted_first[0].hunks());
+}
+
+#[test]
+fn test_commute_conflict() {
+    let first: PatchSet = FIRST.parse().unwrap();
+    let second: PatchSet = "--- a/file
++++ b/file
+@@ -4,3 +4,3 @@
+ three and a half
+-line4
++four
+ line5
+"
+    .parse()
+    .unwrap();
+
+    match first.commute(&second) {
+        Err(Error::Conflict(conflict)) => {
+            assert_eq!("file", conflict.path);
+            assert_eq!(Some(0), conflict.first_hunk);
+            assert_eq!(Some(0), conflict.second_hunk);
+            assert_eq!(Some((3, 5)), conflict.lines);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_commute_no_newline() {
+    let first: PatchSet = "--- a/file\n+++ b/file\n@@ -1,4 +1,4 @@\n-line1\n+one\n line2\n line3\n line4\n\\ No newline at end of file\n"
+        .parse()
+        .unwrap();
+    let second: PatchSet = "--- a/file\n+++ b/file\n@@ -1,4 +1,4 @@\n one\n line2\n line3\n-line4\n\\ No newline at end of file\n+four\n\\ No newline at end of file\n"
+        .parse()
+        .unwrap();
+
+    let (second, first) = first.commute(&second).unwrap();
+
+    let lines: Vec<String> = second[0][0].lines().iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        vec![
+            " line1",
+            " line2",
+            " line3",
+            "-line4",
+            "\\ No newline at end of file",
+            "+four",
+            "\\ No newline at end of file"
+        ],
+        lines
+    );
+    let lines: Vec<String> = first[0][0].lines().iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        vec![
+            "-line1",
+            "+one",
+            " line2",
+            " line3",
+            " four",
+            "\\ No newline at end of file"
+        ],
+        lines
+    );
+}
@@ -0,0 +1,147 @@
+extern crate unidiff;
+
+use unidiff::{Hunk, PatchSet};
+
+const DIFF: &str = "--- a/file
++++ b/file
+@@ -2,14 +2,14 @@ fn main() {
+ line2
+ line3
+ line4
+-line5
++five
+ line6
+ line7
+ line8
+ line9
+ line10
+ line11
+-line12
++twelve
+ line13
+ line14
+ line15
+";
+
+fn lines(hunk: &Hunk) -> Vec<String> {
+    hunk.lines().iter().map(|l| l.to_string()).collect()
+}
+
+fn source() -> String {
+    (1..=20).map(|n| format!("line{}\n", n)).collect()
+}
+
+#[test]
+fn test_reduce_context() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+
+    let reduced = patch.with_context(1);
+    assert_eq!(2, reduced[0].len());
+    let (first, second) = (&reduced[0][0], &reduced[0][1]);
+    assert_eq!(
+        (4, 3, 4, 3),
+        (
+            first.source_start,
+            first.source_length,
+            first.target_start,
+            first.target_length
+        )
+    );
+    assert_eq!(
+        (11, 3, 11, 3),
+        (
+            second.source_start,
+            second.source_length,
+            second.target_start,
+            second.target_length
+        )
+    );
+    assert_eq!("fn main() {", second.section_header);
+    assert_eq!(Some(12), second[1].source_line_no);
+
+    let unchanged = patch.with_context(3);
+    assert_eq!(patch.to_string(), unchanged.to_string());
+}
+
+#[test]
+fn test_expand_context() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+
+    let reduced = patch.with_context(0);
+    assert_eq!(2, reduced[0].len());
+
+    let expanded = reduced
+        .with_context_from_source(5, |_| Some(source()))
+        .unwrap();
+    assert_eq!(1, expanded[0].len());
+    let hunk = &expanded[0][0];
+    assert_eq!(
+        (1, 17, 1, 17),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!(2, hunk.added());
+}
+
+#[test]
+fn test_expand_context_mismatch() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+
+    let source = source().replace("line3\n", "line three\n");
+    assert!(patch
+        .with_context_from_source(3, |_| Some(source.clone()))
+        .is_err());
+}
+
+#[test]
+fn test_reduce_context_no_newline() {
+    let patch: PatchSet = "--- a/file\n+++ b/file\n@@ -1,4 +1,4 @@\n-one\n+1\n two\n three\n-four\n\\ No newline at end of file\n+4\n\\ No newline at end of file\n"
+        .parse()
+        .unwrap();
+
+    let reduced = patch.with_context(0);
+    assert_eq!(2, reduced[0].len());
+    assert_eq!(
+        vec![
+            "-four",
+            "\\ No newline at end of file",
+            "+4",
+            "\\ No newline at end of file"
+        ],
+        lines(&reduced[0][1])
+    );
+
+    let patch: PatchSet = "--- a/file\n+++ b/file\n@@ -1,3 +1,3 @@\n-one\n+1\n two\n three\n\\ No newline at end of file\n"
+        .parse()
+        .unwrap();
+    let reduced = patch.with_context(2);
+    assert_eq!(
+        vec![
+            "-one",
+            "+1",
+            " two",
+            " three",
+            "\\ No newline at end of file"
+        ],
+        lines(&reduced[0][0])
+    );
+}
+
+#[test]
+fn test_expand_context_no_newline() {
+    let patch: PatchSet = "--- a/file\n+++ b/file\n@@ -1 +1 @@\n-one\n+1\n"
+        .parse()
+        .unwrap();
+
+    let expanded = patch
+        .with_context_from_source(3, |_| Some("one\ntwo".to_owned()))
+        .unwrap();
+    assert_eq!(
+        vec!["-one", "+1", " two", "\\ No newline at end of file"],
+        lines(&expanded[0][0])
+    );
+}
@@ -0,0 +1,66 @@
+extern crate unidiff;
+
+use unidiff::{Filter, MatchOn, PatchSet, PatchedFile};
+
+fn paths(patch: &PatchSet) -> Vec<String> {
+    patch.files().iter().map(|f| f.path()).collect()
+}
+
+#[test]
+fn test_filter_include_exclude() {
+    let patch: PatchSet = include_str!("fixtures/git.diff").parse().unwrap();
+
+    let filtered = patch.filter(&Filter::new().include("*_file").exclude("rem*"));
+    assert_eq!(vec!["added_file", "modified_file"], paths(&filtered));
+
+    let filtered = patch.filter(&Filter::new().include("[am]*").include("nothing"));
+    assert_eq!(vec!["added_file", "modified_file"], paths(&filtered));
+
+    let filtered = patch.filter(&Filter::new().exclude("*"));
+    assert!(filtered.is_empty());
+}
+
+#[test]
+fn test_filter_strip() {
+    let patch: PatchSet = include_str!("fixtures/sample0.diff").parse().unwrap();
+
+    let filter = Filter::new()
+        .include("to/*")
+        .match_on(MatchOn::Target)
+        .strip(2);
+    assert_eq!(
+        vec!["/path/to/original", "/dev/null", "/path/to/same"],
+        paths(&patch.filter(&filter))
+    );
+
+    let filter = Filter::new()
+        .include("to/?ame")
+        .match_on(MatchOn::Source)
+        .strip(2);
+    assert_eq!(vec!["/path/to/same"], paths(&patch.filter(&filter)));
+}
+
+#[test]
+fn test_filter_patterns() {
+    let matches = |pattern: &str, name: &str| {
+        Filter::new()
+            .include(pattern)
+            .match_on(MatchOn::Source)
+            .matches(&PatchedFile::new(name, name))
+    };
+    assert!(matches("*", ""));
+    assert!(matches("a*b*c", "axxbyybc"));
+    assert!(matches("*.rs", "src/lib.rs"));
+    assert!(!matches("*.rs", "src/lib.rsx"));
+    assert!(matches("*a?c*", "xxabcabd"));
+    assert!(!matches("a*b", "acbx"));
+    assert!(matches("[!a]*[0-9]", "b_1"));
+    assert!(matches("\\*x", "*x"));
+    assert!(!matches("\\*x", "ax"));
+    assert!(matches("[ab", "[ab"));
+
+    // exponential with a recursive match going back to every star
+    let name = "a".repeat(200);
+    assert!(!matches(&format!("{}b", "*a".repeat(50)), &name));
+    assert!(matches(&"*a".repeat(50), &name));
+}
@@ -0,0 +1,67 @@
+extern crate unidiff;
+
+use unidiff::{Hunk, LineType, PatchSet, PatchedFile};
+
+#[test]
+fn test_git_string_of_git_diff() {
+    for diff in [
+        include_str!("fixtures/stat.diff"),
+        include_str!("fixtures/renames.diff"),
+    ]
+    .iter()
+    {
+        let patch: PatchSet = diff.parse().unwrap();
+        assert_eq!(*diff, patch.to_git_string());
+    }
+
+    let patch: PatchSet = include_str!("fixtures/git.diff").parse().unwrap();
+    assert_eq!(
+        "diff --git a/added_file b/added_file
+deleted file mode 100644
+index 9b710f3..0000000
+--- a/added_file
++++ /dev/null
+@@ -1,4 +0,0 @@
+-This was missing!
+-Adding it now.
+-
+-Only for testing purposes.
+\\ No newline at end of file
+",
+        patch.reverse()[0].to_git_string()
+    );
+}
+
+#[test]
+fn test_git_string_of_other_diff() {
+    let patch: PatchSet = include_str!("fixtures/sample1.diff").parse().unwrap();
+    assert!(patch.to_git_string().starts_with(
+        "diff --git a/path/to/new b/path/to/new
+--- a/path/to/new
++++ b/path/to/new
+@@ -1,3 +1,9 @@
+"
+    ));
+
+    let patch: PatchSet = "--- foo.c.orig\n+++ foo.c\n@@ -1 +1 @@\n-a\n+b\n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        "diff --git a/foo.c b/foo.c\n--- a/foo.c\n+++ b/foo.c\n@@ -1 +1 @@\n-a\n+b\n",
+        patch.to_git_string()
+    );
+
+    let mut hunk = Hunk::new(0, 0, 1, 1, "");
+    hunk.append(unidiff::Line::new("new", LineType::Added));
+    let file = PatchedFile::with_hunks("/dev/null", "b/new.txt", vec![hunk]);
+    assert_eq!(
+        "diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++new
+",
+        file.to_git_string()
+    );
+}
@@ -0,0 +1,80 @@
+extern crate regex;
+extern crate unidiff;
+
+use regex::Regex;
+use unidiff::{Grep, PatchSet};
+
+#[test]
+fn test_grep_lines() {
+    let patch: PatchSet = include_str!("fixtures/sample0.diff").parse().unwrap();
+
+    let grep = Grep::new(Regex::new("important").unwrap());
+    let matches = patch.grep(&grep);
+    let found: Vec<(String, String)> = matches
+        .iter()
+        .map(|m| (m.file.target_file.clone(), m.line.to_string()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                "/path/to/new".to_owned(),
+                "+This is an important".to_owned()
+            ),
+            (
+                "/path/to/new".to_owned(),
+                "+important new additions".to_owned()
+            ),
+            (
+                "/path/to/another_new".to_owned(),
+                "+This is an important".to_owned()
+            ),
+            ("/dev/null".to_owned(), "-This is an important".to_owned()),
+            (
+                "/path/to/same".to_owned(),
+                "+This is an important".to_owned()
+            ),
+            (
+                "/path/to/same".to_owned(),
+                "+important new additions".to_owned()
+            ),
+        ],
+        found
+    );
+
+    let grep = grep.added(false).context(true);
+    let found: Vec<String> = patch
+        .grep(&grep)
+        .iter()
+        .map(|m| m.line.to_string())
+        .collect();
+    assert_eq!(
+        vec![
+            " It is important to spell",
+            "-This is an important",
+            " It is important to spell"
+        ],
+        found
+    );
+}
+
+#[test]
+fn test_grep_hunks() {
+    let patch: PatchSet = include_str!("fixtures/sample0.diff").parse().unwrap();
+
+    let grep = Grep::new(Regex::new("important").unwrap()).removed(false);
+    assert_eq!(3, patch.grep_files(&grep).len());
+
+    let reduced = patch.grep_hunks(&grep);
+    assert_eq!(3, reduced.len());
+    assert_eq!(2, reduced[0].len());
+    assert_eq!("Section Header", reduced[0][0].section_header);
+    assert_eq!(
+        (22, 3, 28, 7),
+        (
+            reduced[0][1].source_start,
+            reduced[0][1].source_length,
+            reduced[0][1].target_start,
+            reduced[0][1].target_length
+        )
+    );
+}
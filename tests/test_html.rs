@@ -0,0 +1,64 @@
+extern crate unidiff;
+
+use unidiff::PatchSet;
+
+const DIFF: &str = "diff --git a/x.rs b/y.rs
+similarity index 90%
+rename from x.rs
+rename to y.rs
+--- a/x.rs
++++ b/y.rs
+@@ -1,2 +1,2 @@ fn <T>
+ a
+-b & c
++\"c\"
+";
+
+#[test]
+fn test_html_unified() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+    let html = patch.html().title("<x>").to_string();
+    assert!(html.starts_with("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>&lt;x&gt;</title>\n<style>"));
+    assert!(html.ends_with("</body>\n</html>\n"));
+    let body = &html[html.find("<body>").unwrap()..];
+    assert_eq!(
+        "<body>
+<div class=\"file\" id=\"f1\">
+<div class=\"file-header\"><a href=\"#f1\">x.rs =&gt; y.rs</a><pre class=\"meta\">similarity index 90%
+rename from x.rs
+rename to y.rs</pre></div>
+<table class=\"unified\">
+<tr class=\"hunk\"><td class=\"num\"></td><td class=\"num\"></td><td class=\"code\">@@ -1,2 +1,2 @@ fn &lt;T&gt;</td></tr>
+<tr><td class=\"num context\" id=\"f1-L1\"><a href=\"#f1-L1\">1</a></td><td class=\"num context\" id=\"f1-R1\"><a href=\"#f1-R1\">1</a></td><td class=\"code context\"> a</td></tr>
+<tr><td class=\"num removed\" id=\"f1-L2\"><a href=\"#f1-L2\">2</a></td><td class=\"num removed\"></td><td class=\"code removed\">-b &amp; c</td></tr>
+<tr><td class=\"num added\"></td><td class=\"num added\" id=\"f1-R2\"><a href=\"#f1-R2\">2</a></td><td class=\"code added\">+&quot;c&quot;</td></tr>
+</table>
+</div>
+</body>
+</html>
+",
+        body
+    );
+}
+
+#[test]
+fn test_html_split() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+    let html = patch.html().split(true).to_string();
+    assert!(html.contains("<table class=\"split\">\n<tr class=\"hunk\"><td class=\"num\"></td><td class=\"code\" colspan=\"3\">@@ -1,2 +1,2 @@ fn &lt;T&gt;</td></tr>\n"));
+    assert!(html.contains("<tr><td class=\"num removed\" id=\"f1-L2\"><a href=\"#f1-L2\">2</a></td><td class=\"code removed\">-b &amp; c</td><td class=\"num added\" id=\"f1-R2\"><a href=\"#f1-R2\">2</a></td><td class=\"code added\">+&quot;c&quot;</td></tr>\n"));
+}
+
+#[test]
+fn test_html_files() {
+    let patch: PatchSet = include_str!("fixtures/stat.diff").parse().unwrap();
+    let html = patch.html().to_string();
+    for (i, _) in patch.files().iter().enumerate() {
+        assert!(html.contains(&format!("<div class=\"file\" id=\"f{}\">", i + 1)));
+    }
+    assert_eq!(
+        patch.len() - 1,
+        html.matches("<table class=\"unified\">").count()
+    );
+    assert_eq!(1, html.matches("<div class=\"binary\">").count());
+}
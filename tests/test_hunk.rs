@@ -1,6 +1,6 @@
 extern crate unidiff;
 
-use unidiff::{Hunk, Line};
+use unidiff::{Hunk, Line, LineType};
 
 #[test]
 fn test_default_is_valid() {
@@ -17,7 +17,7 @@ fn test_missing_data_is_not_valid() {
 #[test]
 fn test_append_context() {
     let mut hunk = Hunk::new(0, 1, 0, 1, "");
-    hunk.append(Line::new("sample line", " "));
+    hunk.append(Line::new("sample line", LineType::Context));
     assert!(hunk.is_valid());
     assert_eq!(hunk.source_lines(), hunk.target_lines());
 }
@@ -25,7 +25,7 @@ fn test_append_context() {
 #[test]
 fn test_append_added_line() {
     let mut hunk = Hunk::new(0, 0, 0, 1, "");
-    hunk.append(Line::new("sample line", "+"));
+    hunk.append(Line::new("sample line", LineType::Added));
     assert!(hunk.is_valid());
     assert_eq!(0, hunk.source_lines().len());
     assert_eq!(1, hunk.target_lines().len());
@@ -34,8 +34,143 @@ fn test_append_added_line() {
 #[test]
 fn test_append_removed_line() {
     let mut hunk = Hunk::new(0, 1, 0, 0, "");
-    hunk.append(Line::new("sample line", "-"));
+    hunk.append(Line::new("sample line", LineType::Removed));
     assert!(hunk.is_valid());
     assert_eq!(1, hunk.source_lines().len());
     assert_eq!(0, hunk.target_lines().len());
 }
+
+#[test]
+fn test_recount() {
+    let mut hunk = Hunk::new(3, 1, 3, 1, "");
+    hunk.append(Line::new("sample line", LineType::Context));
+    hunk.append(Line::new("removed line", LineType::Removed));
+    hunk.append(Line::new("added line", LineType::Added));
+    hunk.append(Line::new("another added line", LineType::Added));
+    assert!(!hunk.is_valid());
+
+    hunk.recount();
+    assert!(hunk.is_valid());
+    assert_eq!(
+        (3, 2, 3, 3),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!(Some(4), hunk[1].source_line_no);
+    assert_eq!(Some(5), hunk[3].target_line_no);
+}
+
+fn sample_hunk() -> Hunk {
+    let patch: unidiff::PatchSet = "--- a/file
++++ b/file
+@@ -1,8 +1,8 @@ section
+ one
+-two
++2
++2.5
+ three
+ four
+ five
+-six
+ seven
+ eight
+"
+    .parse()
+    .unwrap();
+    patch[0][0].clone()
+}
+
+#[test]
+fn test_split() {
+    let hunk = sample_hunk();
+
+    let hunks = hunk.split();
+    assert_eq!(2, hunks.len());
+    assert_eq!(
+        (1, 5, 1, 6),
+        (
+            hunks[0].source_start,
+            hunks[0].source_length,
+            hunks[0].target_start,
+            hunks[0].target_length
+        )
+    );
+    assert_eq!(
+        (3, 6, 4, 5),
+        (
+            hunks[1].source_start,
+            hunks[1].source_length,
+            hunks[1].target_start,
+            hunks[1].target_length
+        )
+    );
+    assert_eq!("section", hunks[1].section_header);
+    assert_eq!(Some(6), hunks[1][3].source_line_no);
+    assert!(hunks[1][3].is_removed());
+    assert!(hunks.iter().all(|h| h.is_valid()));
+}
+
+#[test]
+fn test_merge() {
+    let hunk = sample_hunk();
+    let hunks = hunk.split();
+
+    let merged = hunks[1].merge(&hunks[0]).unwrap();
+    assert_eq!(hunk.to_string(), merged.to_string());
+    assert!(hunks[0].merge(&Hunk::new(20, 0, 20, 0, "")).is_err());
+}
+
+#[test]
+fn test_select() {
+    let hunk = sample_hunk();
+
+    let staged = hunk.select(&[1, 2]);
+    assert!(staged.is_valid());
+    assert_eq!(
+        vec![" one", "-two", "+2", " three", " four", " five", " six", " seven", " eight"],
+        staged
+            .lines()
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>()
+    );
+    assert_eq!(
+        (1, 8, 1, 8),
+        (
+            staged.source_start,
+            staged.source_length,
+            staged.target_start,
+            staged.target_length
+        )
+    );
+    assert_eq!(Some(6), staged[6].target_line_no);
+}
+
+#[test]
+fn test_select_reverse() {
+    let hunk = sample_hunk();
+
+    let unstaged = hunk.select_reverse(&[3, 7]);
+    assert!(unstaged.is_valid());
+    assert_eq!(
+        vec![" one", " 2", "+2.5", " three", " four", " five", "-six", " seven", " eight"],
+        unstaged
+            .lines()
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>()
+    );
+    assert_eq!(
+        (1, 8, 1, 8),
+        (
+            unstaged.source_start,
+            unstaged.source_length,
+            unstaged.target_start,
+            unstaged.target_length
+        )
+    );
+}
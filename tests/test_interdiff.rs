@@ -0,0 +1,158 @@
+extern crate unidiff;
+
+use unidiff::{Error, PatchSet};
+
+const REVISION_1: &str = "--- a/file
++++ b/file
+@@ -1,5 +1,5 @@
+ line1
+-line2
++two
+ line3
+ line4
+ line5
+";
+
+const REVISION_2: &str = "--- a/file
++++ b/file
+@@ -1,5 +1,5 @@
+ line1
+-line2
++TWO
+ line3
+ line4
+ line5
+@@ -6,5 +6,5 @@
+ line6
+ line7
+ line8
+-line9
++nine
+ line10
+";
+
+#[test]
+fn test_interdiff() {
+    let old: PatchSet = REVISION_1.parse().unwrap();
+    let new: PatchSet = REVISION_2.parse().unwrap();
+
+    let patch = old.interdiff(&new).unwrap();
+    assert_eq!(1, patch.len());
+    assert_eq!(1, patch[0].len());
+
+    let hunk = &patch[0][0];
+    assert_eq!(
+        (1, 10, 1, 10),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    let changed: Vec<String> = hunk
+        .lines()
+        .iter()
+        .filter(|l| !l.is_context())
+        .map(|l| l.to_string())
+        .collect();
+    assert_eq!(vec!["-two", "+TWO", "-line9", "+nine"], changed);
+    assert_eq!(Some(9), hunk[9].source_line_no);
+    assert_eq!(Some(9), hunk[10].target_line_no);
+}
+
+#[test]
+fn test_interdiff_identical() {
+    let old: PatchSet = REVISION_2.parse().unwrap();
+
+    let patch = old.interdiff(&old).unwrap();
+    assert!(patch.is_empty());
+}
+
+#[test]
+fn test_interdiff_file_only_in_one_revision() {
+    let old: PatchSet = REVISION_1.parse().unwrap();
+    let new = PatchSet::new();
+
+    let patch = old.interdiff(&new).unwrap();
+    assert_eq!(1, patch.len());
+    assert_eq!(1, patch[0][0].added());
+    assert_eq!("two", patch[0][0][1].value);
+    assert!(patch[0][0][1].is_removed());
+    assert_eq!("line2", patch[0][0][2].value);
+    assert!(patch[0][0][2].is_added());
+}
+
+#[test]
+fn test_interdiff_different_base() {
+    let old: PatchSet = REVISION_1.parse().unwrap();
+    let new: PatchSet = REVISION_1
+        .replace(" line3", " line3 changed")
+        .parse()
+        .unwrap();
+
+    assert!(old.interdiff(&new).is_err());
+}
+
+#[test]
+fn test_interdiff_without_context() {
+    let old: PatchSet = "--- a/f\n+++ b/f\n@@ -5,0 +6,2 @@\n+x\n+y\n"
+        .parse()
+        .unwrap();
+    let new: PatchSet = "--- a/f\n+++ b/f\n@@ -5,0 +6,1 @@\n+x\n".parse().unwrap();
+
+    let patch = old.interdiff(&new).unwrap();
+    assert_eq!(1, patch.len());
+    let hunk = &patch[0][0];
+    assert_eq!(
+        (6, 2, 6, 1),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!("-y", hunk[1].to_string());
+}
+
+#[test]
+fn test_interdiff_header() {
+    let old: PatchSet = REVISION_1.parse().unwrap();
+    let new: PatchSet = REVISION_2.parse().unwrap();
+
+    let patch = old.interdiff(&new).unwrap();
+    assert_eq!("file", patch[0].path());
+    assert!(patch[0]
+        .to_git_string()
+        .starts_with("diff --git a/file b/file\n--- a/file\n+++ b/file\n"));
+}
+
+#[test]
+fn test_interdiff_no_newline() {
+    let old: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+        .parse()
+        .unwrap();
+    let new: PatchSet =
+        "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n"
+            .parse()
+            .unwrap();
+
+    let patch = old.interdiff(&new).unwrap();
+    let lines: Vec<String> = patch[0][0].lines().iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        vec![" a", "-c", "\\ No newline at end of file", "+c"],
+        lines
+    );
+}
+
+#[test]
+fn test_interdiff_malformed_hunk() {
+    let old: PatchSet = "--- a/f\n+++ b/f\n@@ -0,1 +1 @@\n-a\n+b\n".parse().unwrap();
+    let new: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+c\n".parse().unwrap();
+
+    match old[0].interdiff(&new[0]) {
+        Err(Error::Mismatch(message)) => assert_eq!("hunk -0,1 starts before line 1", message),
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -0,0 +1,87 @@
+extern crate unidiff;
+
+use std::convert::TryFrom;
+
+use unidiff::{Line, LineType, PatchSet};
+
+#[test]
+fn test_line_type_conversions() {
+    let line_type = |s: &str| LineType::try_from(s).unwrap();
+    assert_eq!(LineType::Added, line_type("+"));
+    assert_eq!(LineType::Removed, line_type("-"));
+    assert_eq!(LineType::Context, line_type(" "));
+    assert_eq!(LineType::Context, line_type(""));
+    assert_eq!(LineType::NoNewlineMarker, line_type("\\"));
+    assert_eq!(
+        LineType::Combined {
+            parents: 3,
+            added: 0b001,
+            removed: 0b100
+        },
+        line_type("+ -")
+    );
+    for s in ["+", "-", " ", "\\", "++", " -", "+- "].iter() {
+        assert_eq!(*s, line_type(s).to_string());
+        assert_eq!(line_type(s), *s);
+        assert_eq!(*s, String::from(line_type(s)));
+    }
+    assert!(LineType::Context != "");
+    assert!(LineType::Added != "-");
+    assert!(line_type("+ -") != "+-");
+    assert!(line_type("+ -") != "+ - ");
+    assert!(line_type("+ -") != "+ +");
+}
+
+#[test]
+fn test_invalid_line_type() {
+    assert!(LineType::try_from("x").is_err());
+    assert!(LineType::try_from("+x").is_err());
+    assert!(LineType::try_from("\\\\".to_owned()).is_err());
+    assert!(LineType::try_from("+".repeat(33)).is_err());
+    assert_eq!(
+        "Invalid line type: \"x\"",
+        LineType::try_from("x").unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_line_types_of_parsed_lines() {
+    let patch: PatchSet =
+        "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n\n-b\n+c\n\\ No newline at end of file\n"
+            .parse()
+            .unwrap();
+    let types: Vec<LineType> = patch[0][0].lines().iter().map(|l| l.line_type).collect();
+    assert_eq!(
+        vec![
+            LineType::Context,
+            LineType::Context,
+            LineType::Removed,
+            LineType::Added,
+            LineType::NoNewlineMarker
+        ],
+        types
+    );
+    assert_eq!(
+        Line::try_new("c", "+").unwrap(),
+        Line::new("c", LineType::Added)
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_line_with_string_line_type() {
+    use unidiff::{LINE_TYPE_ADDED, LINE_TYPE_EMPTY};
+
+    let line = Line::try_new("", LINE_TYPE_EMPTY).unwrap();
+    assert_eq!(LineType::Context, line.line_type);
+    assert_eq!(
+        LineType::Added,
+        Line::try_new("c", LINE_TYPE_ADDED).unwrap().line_type
+    );
+    let line_type = "-".to_owned();
+    assert_eq!(
+        LineType::Removed,
+        Line::try_new("c", &line_type).unwrap().line_type
+    );
+    assert!(Line::try_new("c", "x").is_err());
+}
@@ -0,0 +1,99 @@
+extern crate unidiff;
+
+use unidiff::PatchSet;
+
+fn lossless(diff: &str) -> PatchSet {
+    let mut patch = PatchSet::new();
+    patch.set_lossless(true);
+    patch.parse(diff).unwrap();
+    patch
+}
+
+#[test]
+fn test_lossless_round_trip() {
+    let fixtures = [
+        include_str!("fixtures/bzr.diff"),
+        include_str!("fixtures/git.diff"),
+        include_str!("fixtures/hand-edited.diff"),
+        include_str!("fixtures/hg.diff"),
+        include_str!("fixtures/renamed.diff"),
+        include_str!("fixtures/renames.diff"),
+        include_str!("fixtures/sample0.diff"),
+        include_str!("fixtures/sample1.diff"),
+        include_str!("fixtures/sample2.diff"),
+        include_str!("fixtures/sample3.diff"),
+        include_str!("fixtures/sample4.diff"),
+        include_str!("fixtures/sample5.diff"),
+        include_str!("fixtures/stat.diff"),
+        include_str!("fixtures/svn.diff"),
+    ];
+    for diff in fixtures.iter() {
+        let patch = lossless(diff);
+        assert_eq!(*diff, patch.to_lossless_string());
+    }
+
+    let diff = "junk\r\n--- a\t2020-01-01\r\n+++ b\r\n@@ -1,2 +1 @@\r\n--- x\r\n-++ y\r\n+z\r\n\\ No newline at end of file";
+    let patch = lossless(diff);
+    assert_eq!(1, patch.len());
+    assert_eq!(2, patch[0][0].removed());
+    assert_eq!(diff, patch.to_lossless_string());
+
+    let mut patch = PatchSet::new();
+    patch.set_lenient(true);
+    patch.set_lossless(true);
+    patch
+        .parse(include_str!("fixtures/hand-edited.diff"))
+        .unwrap();
+    assert_eq!(
+        include_str!("fixtures/hand-edited.diff"),
+        patch.to_lossless_string()
+    );
+}
+
+#[test]
+fn test_lossless_edited() {
+    let diff = include_str!("fixtures/git.diff");
+    let mut patch = lossless(diff);
+    patch[1][0][0].value = "edited".to_owned();
+    patch[1].target_file = "b/renamed_file".to_owned();
+
+    let text = patch.to_lossless_string();
+    assert!(text.starts_with(&patch[0].to_lossless_string()));
+    assert!(text.contains(&format!("{}\n", patch[1][0])));
+    // without rename headers, the file is patched in place under its new name
+    assert!(text.contains(
+        "diff --git a/renamed_file b/renamed_file\n\
+         index c7921f5..8946660 100644\n\
+         --- a/renamed_file\n\
+         +++ b/renamed_file\n"
+    ));
+    assert_eq!(diff.lines().count(), text.lines().count());
+}
+
+#[test]
+fn test_lossless_edited_header() {
+    let diff = "From: someone\n\ndiff --git a/a.txt b/a.txt\nindex 1f38447..0000000 100644\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-a\n+b\n";
+    let patch = lossless(diff);
+    assert_eq!(
+        "From: someone\n\ndiff --git a/src/a.txt b/src/a.txt\nindex 1f38447..0000000 100644\n--- a/src/a.txt\n+++ b/src/a.txt\n@@ -1 +1 @@\n-a\n+b\n",
+        patch.map_paths(|p| format!("src/{}", p)).to_lossless_string()
+    );
+
+    let diff = "junk\n--- a.txt\t2020-01-01\n+++ a.txt\n@@ -1 +1 @@\n-a\n+b\n";
+    let patch = lossless(diff);
+    assert_eq!(
+        "junk\n--- src/a.txt\t2020-01-01\n+++ src/a.txt\n@@ -1 +1 @@\n-a\n+b\n",
+        patch.add_prefix("src/", "src/").to_lossless_string()
+    );
+}
+
+#[test]
+fn test_lossless_off_by_default() {
+    let diff = "junk\n--- a.txt\n+++ a.txt\n@@ -1 +1 @@\n-a\n+b\n";
+    let patch: PatchSet = diff.parse().unwrap();
+    assert!(!patch.is_lossless());
+    assert_eq!(
+        "--- a.txt\n+++ a.txt\n@@ -1,1 +1,1 @@ \n-a\n+b\n",
+        patch.to_lossless_string()
+    );
+}
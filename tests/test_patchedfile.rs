@@ -1,6 +1,6 @@
 extern crate unidiff;
 
-use unidiff::{Hunk, PatchedFile};
+use unidiff::{Hunk, PatchSet, PatchedFile};
 
 #[test]
 fn test_is_added_file() {
@@ -22,3 +22,42 @@ fn test_is_modified_file() {
     let file = PatchedFile::with_hunks("a", "b", vec![hunk]);
     assert!(file.is_modified_file());
 }
+
+#[test]
+fn test_line_mapping() {
+    let patch: PatchSet = include_str!("fixtures/sample1.diff").parse().unwrap();
+    let file = &patch[0];
+
+    assert_eq!(Some(7), file.source_to_target(1));
+    assert_eq!(None, file.source_to_target(8));
+    assert_eq!(Some(16), file.source_to_target(16));
+    assert_eq!(None, file.source_to_target(17));
+    assert_eq!(Some(24), file.source_to_target(24));
+    assert_eq!(Some(29), file.source_to_target(25));
+
+    assert_eq!(None, file.target_to_source(1));
+    assert_eq!(Some(1), file.target_to_source(7));
+    assert_eq!(Some(16), file.target_to_source(16));
+    assert_eq!(None, file.target_to_source(26));
+    assert_eq!(Some(25), file.target_to_source(29));
+}
+
+#[test]
+fn test_positions() {
+    let patch: PatchSet = include_str!("fixtures/sample1.diff").parse().unwrap();
+    let file = &patch[0];
+
+    assert_eq!(Some(1), file.target_line_position(1));
+    assert_eq!(Some(11), file.target_line_position(11));
+    assert_eq!(Some(11), file.source_line_position(5));
+    assert_eq!(Some(14), file.source_line_position(8));
+    assert_eq!(Some(21), file.target_line_position(14));
+    assert_eq!(None, file.source_line_position(1000));
+
+    assert_eq!(None, file.line_at_position(10));
+    let line = file.line_at_position(14).unwrap();
+    assert!(line.is_removed());
+    assert_eq!(Some(8), line.source_line_no);
+    assert_eq!(Some(14), file.diff_line_position(line.diff_line_no));
+    assert_eq!(Some(1), file.diff_line_position(4));
+}
@@ -417,3 +417,99 @@ fn test_parse_patchset_from_str() {
     // three hunks
     assert_eq!(3, patch[0].len());
 }
+
+#[test]
+fn test_parse_lenient() {
+    let buf = include_str!("fixtures/hand-edited.diff");
+
+    let mut patch = PatchSet::new();
+    patch.parse(&buf).unwrap();
+    assert!(!patch[0][0].is_valid());
+
+    let mut patch = PatchSet::new();
+    patch.set_lenient(true);
+    patch.parse(&buf).unwrap();
+
+    assert_eq!(2, patch.len());
+    assert_eq!(2, patch[0].len());
+    assert!(patch[0][0].is_valid());
+    assert_eq!(6, patch[0][0].len());
+    assert_eq!(
+        (1, 4, 1, 5),
+        (
+            patch[0][0].source_start,
+            patch[0][0].source_length,
+            patch[0][0].target_start,
+            patch[0][0].target_length
+        )
+    );
+    assert_eq!(Some(5), patch[0][0][5].target_line_no);
+    // starts are kept as they are until recounted
+    assert_eq!(10, patch[0][1].target_start);
+    assert_eq!(1, patch[1].added());
+
+    patch.recount();
+    assert_eq!(11, patch[0][1].target_start);
+    assert_eq!(Some(12), patch[0][1][2].target_line_no);
+}
+
+#[test]
+fn test_parse_omitted_hunk_lengths() {
+    let buf = "--- a/file\n+++ b/file\n@@ -1 +1 @@\n-a\n+b\n@@ -5 +5,2 @@\n c\n+d\n";
+
+    let mut patch = PatchSet::new();
+    patch.parse(buf).unwrap();
+
+    assert_eq!(1, patch.len());
+    assert_eq!(2, patch[0].len());
+    let hunks: Vec<(usize, usize, usize, usize)> = patch[0]
+        .hunks()
+        .iter()
+        .map(|h| {
+            (
+                h.source_start,
+                h.source_length,
+                h.target_start,
+                h.target_length,
+            )
+        })
+        .collect();
+    assert_eq!(vec![(1, 1, 1, 1), (5, 1, 5, 2)], hunks);
+    assert!(patch[0][0].is_valid());
+    assert!(patch[0][1].is_valid());
+    assert_eq!(Some(6), patch[0][1][1].target_line_no);
+}
+
+#[test]
+fn test_parse_binary_files() {
+    let buf = "diff --git a/image.png b/image.png\n\
+               index 1f38447..8946660 100644\n\
+               Binary files a/image.png and b/image.png differ\n\
+               diff --git a/file b/file\n\
+               --- a/file\n+++ b/file\n@@ -1 +1 @@\n-a\n+b\n";
+
+    let mut patch = PatchSet::new();
+    patch.parse(buf).unwrap();
+
+    assert_eq!(2, patch.len());
+    assert!(patch[0].is_binary_file());
+    assert_eq!("a/image.png", patch[0].source_file);
+    assert_eq!("b/image.png", patch[0].target_file);
+    assert_eq!(0, patch[0].len());
+    assert!(!patch[1].is_binary_file());
+    assert_eq!(1, patch[1].len());
+
+    // without git headers, as `diff -r` writes them
+    let buf = "Binary files old/data.bin and new/data.bin differ\n\
+               --- old/file\n+++ new/file\n@@ -1 +1 @@\n-a\n+b\n";
+
+    let mut patch = PatchSet::new();
+    patch.parse(buf).unwrap();
+
+    assert_eq!(2, patch.len());
+    assert!(patch[0].is_binary_file());
+    assert_eq!("old/data.bin", patch[0].source_file);
+    assert_eq!("new/data.bin", patch[0].target_file);
+    assert!(!patch[1].is_binary_file());
+    assert_eq!(1, patch[1].len());
+}
@@ -0,0 +1,126 @@
+extern crate unidiff;
+
+use unidiff::{ExtendedHeader, PatchSet};
+
+fn names(patch: &PatchSet) -> Vec<(String, String)> {
+    patch
+        .files()
+        .iter()
+        .map(|f| (f.source_file.clone(), f.target_file.clone()))
+        .collect()
+}
+
+#[test]
+fn test_strip_prefix() {
+    let patch: PatchSet = include_str!("fixtures/git.diff").parse().unwrap();
+
+    let stripped = patch.strip_prefix(1).unwrap();
+    assert_eq!(
+        vec![
+            ("/dev/null".to_owned(), "added_file".to_owned()),
+            ("modified_file".to_owned(), "modified_file".to_owned()),
+            ("removed_file".to_owned(), "/dev/null".to_owned()),
+        ],
+        names(&stripped)
+    );
+    assert_eq!(patch.files()[1].hunks(), stripped.files()[1].hunks());
+
+    assert_eq!(
+        patch.to_string(),
+        patch.strip_prefix(0).unwrap().to_string()
+    );
+    assert!(patch.strip_prefix(2).is_err());
+}
+
+#[test]
+fn test_add_prefix() {
+    let patch: PatchSet = include_str!("fixtures/git.diff").parse().unwrap();
+
+    let moved = patch
+        .strip_prefix(1)
+        .unwrap()
+        .add_prefix("a/sub/", "b/sub/");
+    assert_eq!(
+        vec![
+            ("/dev/null".to_owned(), "b/sub/added_file".to_owned()),
+            (
+                "a/sub/modified_file".to_owned(),
+                "b/sub/modified_file".to_owned()
+            ),
+            ("a/sub/removed_file".to_owned(), "/dev/null".to_owned()),
+        ],
+        names(&moved)
+    );
+    assert_eq!(
+        vec!["sub/added_file", "sub/modified_file", "sub/removed_file"],
+        moved.files().iter().map(|f| f.path()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_map_paths() {
+    let patch: PatchSet = include_str!("fixtures/renamed.diff").parse().unwrap();
+    assert_eq!(
+        &[
+            ExtendedHeader::SimilarityIndex(100),
+            ExtendedHeader::RenameFrom("foo/bar/baz/bash.rs".to_owned()),
+            ExtendedHeader::RenameTo("lol/loo/lee/lah.rs".to_owned()),
+        ],
+        patch[0].extended_headers()
+    );
+
+    let mapped = patch.map_paths(|p| format!("vendor/{}", p));
+    assert_eq!("a/vendor/foo/bar/baz/bash.rs", mapped[0].source_file);
+    assert_eq!("b/vendor/lol/loo/lee/lah.rs", mapped[0].target_file);
+    assert_eq!(
+        &[
+            ExtendedHeader::SimilarityIndex(100),
+            ExtendedHeader::RenameFrom("vendor/foo/bar/baz/bash.rs".to_owned()),
+            ExtendedHeader::RenameTo("vendor/lol/loo/lee/lah.rs".to_owned()),
+        ],
+        mapped[0].extended_headers()
+    );
+
+    let patch: PatchSet = include_str!("fixtures/git.diff").parse().unwrap();
+    let mapped = patch.map_paths(|p| p.to_uppercase());
+    assert_eq!(
+        vec![
+            ("/dev/null".to_owned(), "b/ADDED_FILE".to_owned()),
+            ("a/MODIFIED_FILE".to_owned(), "b/MODIFIED_FILE".to_owned()),
+            ("a/REMOVED_FILE".to_owned(), "/dev/null".to_owned()),
+        ],
+        names(&mapped)
+    );
+}
+
+#[test]
+fn test_prefix_rename_headers() {
+    let patch: PatchSet = include_str!("fixtures/renamed.diff").parse().unwrap();
+
+    let stripped = patch.strip_prefix(2).unwrap();
+    assert_eq!("bar/baz/bash.rs", stripped[0].source_file);
+    assert_eq!("loo/lee/lah.rs", stripped[0].target_file);
+    assert_eq!(
+        &[
+            ExtendedHeader::SimilarityIndex(100),
+            ExtendedHeader::RenameFrom("bar/baz/bash.rs".to_owned()),
+            ExtendedHeader::RenameTo("loo/lee/lah.rs".to_owned()),
+        ],
+        stripped[0].extended_headers()
+    );
+
+    let moved = stripped.add_prefix("a/vendor/", "b/vendor/");
+    assert_eq!("a/vendor/bar/baz/bash.rs", moved[0].source_file);
+    assert_eq!("b/vendor/loo/lee/lah.rs", moved[0].target_file);
+    assert_eq!(
+        &[
+            ExtendedHeader::SimilarityIndex(100),
+            ExtendedHeader::RenameFrom("vendor/bar/baz/bash.rs".to_owned()),
+            ExtendedHeader::RenameTo("vendor/loo/lee/lah.rs".to_owned()),
+        ],
+        moved[0].extended_headers()
+    );
+    assert!(moved
+        .to_git_string()
+        .starts_with("diff --git a/vendor/bar/baz/bash.rs b/vendor/loo/lee/lah.rs\n"));
+}
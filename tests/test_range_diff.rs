@@ -0,0 +1,63 @@
+extern crate unidiff;
+
+use unidiff::{PatchSet, RangeDiff};
+
+fn patch(path: &str, added: &[&str]) -> PatchSet {
+    let mut diff = format!(
+        "--- a/{}\n+++ b/{}\n@@ -1,2 +1,{} @@\n first\n",
+        path,
+        path,
+        added.len() + 2
+    );
+    for line in added {
+        diff.push_str(&format!("+{}\n", line));
+    }
+    diff.push_str(" last\n");
+    diff.parse().unwrap()
+}
+
+#[test]
+fn test_range_diff() {
+    let old = vec![
+        patch("one.txt", &["a", "b", "c", "d", "e"]),
+        patch("two.txt", &["f", "g", "h", "i", "j"]),
+        patch("three.txt", &["k", "l", "m", "n", "o"]),
+    ];
+    let new = vec![
+        patch("one.txt", &["a", "b", "C", "d", "e"]),
+        patch("three.txt", &["k", "l", "m", "n", "o"]),
+        patch("four.txt", &["p", "q", "r", "s", "t"]),
+    ];
+
+    let range_diff = RangeDiff::new(&old, &new);
+    let entries = range_diff.entries();
+    assert_eq!(4, entries.len());
+
+    assert_eq!((Some(0), Some(0)), (entries[0].old, entries[0].new));
+    assert!(entries[0].is_changed());
+    assert_eq!(1, entries[0].hunks.len());
+    assert_eq!("+c", entries[0].hunks[0][3].value);
+    assert!(entries[0].hunks[0][3].is_removed());
+
+    assert_eq!((Some(1), None), (entries[1].old, entries[1].new));
+    assert!(entries[1].is_dropped());
+
+    assert_eq!((Some(2), Some(1)), (entries[2].old, entries[2].new));
+    assert!(entries[2].is_unchanged());
+    assert!(!entries[2].reordered);
+
+    assert_eq!((None, Some(2)), (entries[3].old, entries[3].new));
+    assert!(entries[3].is_added());
+}
+
+#[test]
+fn test_range_diff_reordered() {
+    let first = patch("one.txt", &["a", "b", "c", "d", "e"]);
+    let second = patch("two.txt", &["f", "g", "h", "i", "j"]);
+
+    let range_diff = RangeDiff::new(&[first.clone(), second.clone()], &[second, first]);
+    assert_eq!(2, range_diff.len());
+    assert!(range_diff.entries().iter().all(|e| e.is_unchanged()));
+    assert!(range_diff.entries().iter().all(|e| e.reordered));
+    assert_eq!("2: = 1:\n1: = 2:", range_diff.to_string());
+}
@@ -0,0 +1,141 @@
+extern crate unidiff;
+
+use std::io::{self, BufReader, Read};
+
+use unidiff::{Error, PatchReader, PatchSet, PatchedFile};
+
+#[test]
+fn test_reader_same_as_patchset() {
+    for diff in [
+        include_str!("fixtures/bzr.diff"),
+        include_str!("fixtures/git.diff"),
+        include_str!("fixtures/hg.diff"),
+        include_str!("fixtures/renames.diff"),
+        include_str!("fixtures/sample1.diff"),
+        include_str!("fixtures/sample3.diff"),
+        include_str!("fixtures/stat.diff"),
+        include_str!("fixtures/svn.diff"),
+    ]
+    .iter()
+    {
+        let patch: PatchSet = diff.parse().unwrap();
+        let mut reader = PatchReader::new(diff.as_bytes());
+        let files: Vec<PatchedFile> = reader.by_ref().collect::<Result<_, _>>().unwrap();
+        assert_eq!(patch.files(), &files[..]);
+        assert_eq!(patch.trailer(), reader.trailer());
+    }
+}
+
+#[test]
+fn test_reader_empty_hunk() {
+    for diff in [
+        "--- a/a\n+++ b/a\n@@ -0,0 +0,0 @@\n--- a/b\n+++ b/b\n@@ -1 +1 @@\n-c\n+d\n",
+        "diff --git a/a b/a\n--- a/a\n+++ b/a\n@@ -0,0 +0,0 @@\n\
+         --- a/b\n+++ b/b\n@@ -1 +1 @@\n-c\n+d\n",
+        "--- a/a\n+++ b/a\n@@ -0,0 +0,0 @@\n\\ No newline at end of file\n\
+         --- a/b\n+++ b/b\n@@ -1 +1 @@\n-c\n+d\n",
+    ]
+    .iter()
+    {
+        let mut patch = PatchSet::new();
+        let parsed = patch.parse(diff);
+        let files: Result<Vec<PatchedFile>, Error> = PatchReader::new(diff.as_bytes()).collect();
+        match (parsed, files) {
+            (Ok(()), Ok(files)) => assert_eq!(patch.files(), &files[..]),
+            (Err(e), Err(reader_e)) => assert_eq!(e.to_string(), reader_e.to_string()),
+            (parsed, files) => panic!("{:?} differs from {:?}", parsed, files),
+        }
+    }
+}
+
+#[test]
+fn test_reader_trailer() {
+    let diff = "intro\n--- a/a\n+++ b/a\n@@ -1 +1 @@\n-a\n+b\nbetween\n\
+                --- a/b\n+++ b/b\n@@ -1 +1 @@\n-c\n+d\n-- \n2.30.0\n";
+    let patch: PatchSet = diff.parse().unwrap();
+    assert_eq!("-- \n2.30.0\n", patch.trailer());
+
+    let mut reader = PatchReader::new(diff.as_bytes());
+    let files: Vec<PatchedFile> = reader.by_ref().collect::<Result<_, _>>().unwrap();
+    assert_eq!(patch.files(), &files[..]);
+    assert_eq!(patch.trailer(), reader.trailer());
+
+    let mut reader = PatchReader::new("no diff here\n".as_bytes());
+    assert!(reader.next().is_none());
+    assert_eq!("no diff here\n", reader.trailer());
+}
+
+#[test]
+fn test_reader_git_log() {
+    let log = "commit 1\n\
+               \n    first\n\n\
+               diff --git a/a b/a\n\
+               --- a/a\n+++ b/a\n\
+               @@ -1,2 +1,2 @@\n\
+               --- not a header\n\
+               +++ not a header either\n\
+               \x20x\n\
+               commit 2\n\
+               \n    second\n\n\
+               diff --git a/b b/b\n\
+               new file mode 100644\n\
+               --- /dev/null\n+++ b/b\n\
+               @@ -0,0 +1 @@\n\
+               +b\n";
+    let files: Vec<PatchedFile> = PatchReader::new(log.as_bytes())
+        .lossless(true)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(2, files.len());
+    assert_eq!("a", files[0].path());
+    assert_eq!(1, files[0].removed());
+    assert_eq!(3, files[0].hunks()[0].lines().len());
+    assert!(files[1].is_added_file());
+    let text: String = files.iter().map(|f| f.to_lossless_string()).collect();
+    assert_eq!(log, text);
+}
+
+#[test]
+fn test_reader_lenient() {
+    let diff = "--- a/a\n+++ b/a\n@@ -1 +1 @@\n-a\n+b\n+c\n--- a/b\n+++ b/b\n@@ -1 +1 @@\n-d\n+e\n";
+    let files: Vec<PatchedFile> = PatchReader::new(diff.as_bytes())
+        .lenient(true)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(2, files.len());
+    assert_eq!(2, files[0].added());
+    assert_eq!("b", files[1].path());
+}
+
+struct Failing;
+
+impl Read for Failing {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::other("broken pipe"))
+    }
+}
+
+#[test]
+fn test_reader_io_error() {
+    let diff = "--- a/a\n+++ b/a\n@@ -1 +1 @@\n-a\n+b\n";
+    let reader = diff.as_bytes().chain(Failing);
+    let mut files = PatchReader::new(BufReader::new(reader));
+    match files.next() {
+        Some(Err(Error::Io(message))) => assert_eq!("broken pipe", message),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(files.next().is_none());
+}
+
+#[test]
+fn test_reader_target_without_source() {
+    let diff = "--- a/a\n+++ b/a\n@@ -1 +1 @@\n-a\n+b\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n";
+    match PatchSet::new().parse(diff) {
+        Err(Error::TargetWithoutSource(message)) => assert_eq!("line 6: +++ b/x", message),
+        other => panic!("unexpected {:?}", other),
+    }
+    match PatchReader::new(diff.as_bytes()).next() {
+        Some(Err(Error::TargetWithoutSource(message))) => assert_eq!("line 6: +++ b/x", message),
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "serde")]
+extern crate unidiff;
+
+use unidiff::{ExtendedHeader, LineType, PatchSet};
+
+#[test]
+fn test_serialize_schema() {
+    let patch: PatchSet = "diff --git a/f b/f\nold mode 100644\nnew mode 100755\n--- a/f\n+++ b/f\n@@ -1 +1 @@ fn\n-old\n+new\n"
+        .parse()
+        .unwrap();
+    let json: serde_json::Value = serde_json::to_value(&patch).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "files": [{
+                "source_file": "a/f",
+                "source_timestamp": "",
+                "target_file": "b/f",
+                "target_timestamp": "",
+                "extended_headers": [{"old_mode": "100644"}, {"new_mode": "100755"}],
+                "binary": false,
+                "hunks": [{
+                    "added": 1,
+                    "removed": 1,
+                    "source_start": 1,
+                    "source_length": 1,
+                    "target_start": 1,
+                    "target_length": 1,
+                    "section_header": "fn",
+                    "lines": [
+                        {
+                            "source_line_no": 1,
+                            "target_line_no": null,
+                            "diff_line_no": 7,
+                            "line_type": "-",
+                            "value": "old"
+                        },
+                        {
+                            "source_line_no": null,
+                            "target_line_no": 1,
+                            "diff_line_no": 8,
+                            "line_type": "+",
+                            "value": "new"
+                        }
+                    ]
+                }]
+            }]
+        }),
+        json
+    );
+}
+
+#[test]
+fn test_serde_round_trip() {
+    for diff in [
+        include_str!("fixtures/git.diff"),
+        include_str!("fixtures/stat.diff"),
+        include_str!("fixtures/sample1.diff"),
+    ]
+    .iter()
+    {
+        let patch: PatchSet = diff.parse().unwrap();
+        let json = serde_json::to_string(&patch).unwrap();
+        let back: PatchSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(patch.to_string(), back.to_string());
+        assert_eq!(patch.files(), back.files());
+        for (file, back_file) in patch.files().iter().zip(back.files()) {
+            for (hunk, back_hunk) in file.hunks().iter().zip(back_file.hunks()) {
+                assert_eq!(hunk.is_valid(), back_hunk.is_valid());
+            }
+        }
+    }
+
+    let header: ExtendedHeader = serde_json::from_str(r#"{"similarity_index": 90}"#).unwrap();
+    assert_eq!(ExtendedHeader::SimilarityIndex(90), header);
+
+    let line_type: LineType = serde_json::from_str(r#"" +""#).unwrap();
+    assert_eq!(line_type, " +");
+    assert!(serde_json::from_str::<LineType>(r#""x""#).is_err());
+}
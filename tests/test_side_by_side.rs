@@ -0,0 +1,69 @@
+extern crate unidiff;
+
+use unidiff::PatchSet;
+
+#[test]
+fn test_paired_lines() {
+    let patch: PatchSet =
+        "--- a/f\n+++ b/f\n@@ -1,6 +1,5 @@\n a\n-b\n-c\n-d\n+B\n+C\n e\n-f\n+F\n\\ No newline at end of file\n"
+            .parse()
+            .unwrap();
+    let pairs: Vec<(Option<String>, Option<String>)> = patch[0][0]
+        .paired_lines()
+        .iter()
+        .map(|(s, t)| (s.map(|l| l.to_string()), t.map(|l| l.to_string())))
+        .collect();
+    let line = |s: &str| Some(s.to_owned());
+    assert_eq!(
+        vec![
+            (line(" a"), line(" a")),
+            (line("-b"), line("+B")),
+            (line("-c"), line("+C")),
+            (line("-d"), None),
+            (line(" e"), line(" e")),
+            (line("-f"), line("+F")),
+            (None, line("\\ No newline at end of file")),
+        ],
+        pairs
+    );
+}
+
+#[test]
+fn test_side_by_side() {
+    let patch: PatchSet = include_str!("fixtures/sample1.diff").parse().unwrap();
+    let text = patch.side_by_side().width(40).to_string();
+    assert_eq!(
+        vec![
+            "/path/to/original  | /path/to/new",
+            "@@ -1,3 +1,9 @@",
+            "                   |  1 +This is an im…",
+            "                   |  2 +notice! It sh…",
+        ],
+        text.lines().take(4).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_side_by_side_width() {
+    let patch: PatchSet = "--- a/名前\n+++ b/名前\n@@ -1 +1 @@\n-x\tone\n+二三四五六七八九十\n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        "a/名前         | b/名前\n\
+         @@ -1 +1 @@\n\
+         1 -x       one | 1 +二三四五六…\n",
+        patch.side_by_side().width(31).to_string()
+    );
+    assert_eq!(
+        "a/名前         | b/名前\n\
+         @@ -1 +1 @@\n\
+         1 -x   one     | 1 +二三四五六\n\
+         \x20              |    七八九十\n",
+        patch
+            .side_by_side()
+            .width(31)
+            .wrap(true)
+            .tab_width(4)
+            .to_string()
+    );
+}
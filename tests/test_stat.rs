@@ -0,0 +1,127 @@
+extern crate unidiff;
+
+use unidiff::{FileStat, Filter, PatchSet};
+
+#[test]
+fn test_file_stat() {
+    let patch: PatchSet = include_str!("fixtures/stat.diff").parse().unwrap();
+    let stat = patch.diffstat();
+    assert_eq!(
+        FileStat {
+            name: "bin.dat".to_owned(),
+            added: 0,
+            removed: 0,
+            binary: true,
+        },
+        stat.files()[0]
+    );
+    assert_eq!("{lib => src}/moved.rs", stat.files()[6].name);
+    assert_eq!((13, 175), (stat.added(), stat.removed()));
+
+    let patch: PatchSet = include_str!("fixtures/renames.diff").parse().unwrap();
+    let names: Vec<String> = patch
+        .diffstat()
+        .files()
+        .iter()
+        .map(|f| f.name.clone())
+        .collect();
+    assert_eq!(
+        vec!["foo => bar", "dir/{sub => }/file", "x/y => z/y2"],
+        names
+    );
+}
+
+#[test]
+fn test_diffstat() {
+    let patch: PatchSet = include_str!("fixtures/stat.diff").parse().unwrap();
+    assert_eq!(
+        " bin.dat                                            | Bin
+ docs/gone.md                                       |  20 ---
+ new.txt                                            |   3 +
+ small.txt                                          |   8 +-
+ src/big.txt                                        | 150 ---------------------
+ ..._a_really_long_file_name_that_goes_on_and_on.rs |   4 +
+ {lib => src}/moved.rs                              |   1 +
+ ünïcödé_名前.txt                                   |   2 +-
+ 8 files changed, 13 insertions(+), 175 deletions(-)",
+        patch.diffstat().to_string()
+    );
+    assert_eq!(
+        " bin.dat                   | Bin
+ docs/gone.md              |  20 -
+ new.txt                   |   3 +
+ small.txt                 |   8 +-
+ src/big.txt               | 150 ------
+ ...that_goes_on_and_on.rs |   4 +
+ {lib => src}/moved.rs     |   1 +
+ ünïcödé_名前.txt          |   2 +-
+ 8 files changed, 13 insertions(+), 175 deletions(-)",
+        patch.diffstat().width(40).to_string()
+    );
+    assert_eq!(" 0 files changed", PatchSet::new().diffstat().to_string());
+}
+
+#[test]
+fn test_numstat_shortstat() {
+    let patch: PatchSet = include_str!("fixtures/stat.diff").parse().unwrap();
+    assert_eq!(
+        "-\t-\tbin.dat
+0\t20\tdocs/gone.md
+3\t0\tnew.txt
+4\t4\tsmall.txt
+0\t150\tsrc/big.txt
+4\t0\tsrc/deeply/nested/directory/structure/with_a_really_long_file_name_that_goes_on_and_on.rs
+1\t0\t{lib => src}/moved.rs
+1\t1\tünïcödé_名前.txt",
+        patch.numstat()
+    );
+    assert_eq!(
+        " 8 files changed, 13 insertions(+), 175 deletions(-)",
+        patch.shortstat()
+    );
+    let patch: PatchSet = include_str!("fixtures/renames.diff").parse().unwrap();
+    assert_eq!(
+        " 3 files changed, 0 insertions(+), 0 deletions(-)",
+        patch.shortstat()
+    );
+}
+
+#[test]
+fn test_dirstat() {
+    let patch: PatchSet = include_str!("fixtures/stat.diff").parse().unwrap();
+    let patch = patch.filter(&Filter::new().exclude("bin.dat"));
+
+    assert_eq!("  10.6% docs/\n  82.4% src/", patch.dirstat().to_string());
+    assert_eq!(
+        "  10.6% docs/
+   2.1% src/deeply/nested/directory/structure/
+  80.3% src/",
+        patch.dirstat().limit(0).to_string()
+    );
+    assert_eq!(
+        vec![
+            ("docs/".to_owned(), 106),
+            ("src/deeply/nested/directory/structure/".to_owned(), 21),
+            ("src/".to_owned(), 824),
+        ],
+        patch.dirstat().limit(0).cumulative(true).entries()
+    );
+    assert_eq!(
+        "  14.2% docs/
+  14.2% src/deeply/nested/directory/structure/
+  28.5% src/",
+        patch.dirstat().by_file(true).limit(0).to_string()
+    );
+    assert_eq!(
+        "  42.8% src/",
+        patch
+            .dirstat()
+            .by_file(true)
+            .cumulative(true)
+            .limit(200)
+            .to_string()
+    );
+
+    let patch: PatchSet = include_str!("fixtures/renames.diff").parse().unwrap();
+    assert!(patch.dirstat().entries().is_empty());
+}
@@ -0,0 +1,135 @@
+extern crate unidiff;
+
+use unidiff::{ExtendedHeader, FileHeader, HunkHeader, LineRef, PatchSet, Visitor};
+
+/// Visitor recording the events as text
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl<'a> Visitor<'a> for Events {
+    fn on_file_header(&mut self, header: FileHeader<'a>, diff_line_no: usize) {
+        self.0.push(format!("{} file {:?}", diff_line_no, header));
+    }
+
+    fn on_extended_header(&mut self, header: ExtendedHeader, diff_line_no: usize) {
+        self.0.push(format!("{} extended {}", diff_line_no, header));
+    }
+
+    fn on_hunk_header(&mut self, header: HunkHeader<'a>, diff_line_no: usize) {
+        self.0.push(format!(
+            "{} hunk -{},{} +{},{}",
+            diff_line_no,
+            header.source_start,
+            header.source_length,
+            header.target_start,
+            header.target_length
+        ));
+    }
+
+    fn on_line(&mut self, line: LineRef<'a>) {
+        self.0.push(format!("{} line {}", line.diff_line_no, line));
+    }
+
+    fn on_binary(&mut self, line: &'a str, diff_line_no: usize) {
+        self.0.push(format!("{} binary {}", diff_line_no, line));
+    }
+
+    fn on_garbage(&mut self, line: &'a str, diff_line_no: usize) {
+        self.0.push(format!("{} garbage {}", diff_line_no, line));
+    }
+
+    fn on_file_end(&mut self, diff_line_no: usize) {
+        self.0.push(format!("{} end", diff_line_no));
+    }
+}
+
+#[test]
+fn test_visit_events() {
+    let diff = "commit 1\n\
+                diff --git a/a b/a\n\
+                index 1..2 100644\n\
+                --- a/a\n\
+                +++ b/a\n\
+                @@ -1 +1 @@\n\
+                -x\n\
+                +y\n\
+                diff --git a/b b/b\n\
+                new file mode 100644\n\
+                Binary files /dev/null and b/b differ\n";
+    let mut events = Events::default();
+    unidiff::visit(diff, &mut events).unwrap();
+    assert_eq!(
+        vec![
+            "1 garbage commit 1",
+            "2 file Git { source_file: \"a/a\", target_file: \"b/a\" }",
+            "3 extended index 1..2 100644",
+            "5 file Unified { source_file: \"a/a\", source_timestamp: None, \
+             target_file: \"b/a\", target_timestamp: None }",
+            "6 hunk -1,1 +1,1",
+            "7 line -x",
+            "8 line +y",
+            "9 end",
+            "9 file Git { source_file: \"a/b\", target_file: \"b/b\" }",
+            "10 extended new file mode 100644",
+            "11 binary Binary files /dev/null and b/b differ",
+            "12 end",
+        ],
+        events.0
+    );
+}
+
+#[test]
+fn test_visit_lenient_recounts_hunks() {
+    let diff = "--- a/a\n+++ b/a\n@@ -1,5 +1,5 @@\n a\n-b\n+c\n+d\n";
+    let mut events = Events::default();
+    unidiff::visit_lenient(diff, &mut events).unwrap();
+    assert_eq!("3 hunk -1,2 +1,3", events.0[1]);
+
+    let mut patch = PatchSet::new();
+    patch.set_lenient(true);
+    patch.parse(diff).unwrap();
+    assert_eq!(2, patch[0].hunks()[0].source_length);
+}
+
+#[test]
+fn test_visit_counts_without_building() {
+    #[derive(Default)]
+    struct Counts {
+        files: usize,
+        added: usize,
+        removed: usize,
+    }
+
+    impl<'a> Visitor<'a> for Counts {
+        fn on_file_header(&mut self, header: FileHeader<'a>, _diff_line_no: usize) {
+            if let FileHeader::Git { .. } = header {
+                self.files += 1;
+            }
+        }
+
+        fn on_line(&mut self, line: LineRef<'a>) {
+            if line.is_added() {
+                self.added += 1;
+            } else if line.is_removed() {
+                self.removed += 1;
+            }
+        }
+    }
+
+    let diff = include_str!("fixtures/git.diff");
+    let patch: PatchSet = diff.parse().unwrap();
+    let mut counts = Counts::default();
+    unidiff::visit(diff, &mut counts).unwrap();
+    assert_eq!(patch.len(), counts.files);
+    let added: usize = patch.files().iter().map(|f| f.added()).sum();
+    let removed: usize = patch.files().iter().map(|f| f.removed()).sum();
+    assert_eq!(added, counts.added);
+    assert_eq!(removed, counts.removed);
+}
+
+#[test]
+fn test_visit_errors() {
+    let mut events = Events::default();
+    assert!(unidiff::visit("@@ -1 +1 @@\n-a\n+b\n", &mut events).is_err());
+    assert!(unidiff::visit("+++ b/x\n@@ -1 +1 @@\n-a\n+b\n", &mut events).is_err());
+}